@@ -0,0 +1,37 @@
+// An example generating and meshing a few chunks without Bevy, the way a server or tool would.
+// Run it with `cargo run --example headless --no-default-features`.
+
+use voxel::{
+    BlockRegistry, ChunkMesh, ChunkPos, GenerationSettings, MeshAlgorithm, VoxelWorld, WorldConfig,
+};
+
+fn main() {
+    let settings = GenerationSettings::default();
+    let emission = BlockRegistry::default().emission();
+    // Smaller chunks of larger voxels than the defaults, any multiple of 8 works as a chunk size.
+    let config = WorldConfig::new(32, 0.25);
+    let mut world = VoxelWorld::new(config);
+
+    // Generate the chunks around the origin, the light spreads between them as they are inserted.
+    for z in -1..=1 {
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let mut chunk = ChunkMesh::new(ChunkPos { x, y, z }, config);
+                chunk.populate(&settings);
+                world.insert(chunk, &emission);
+            }
+        }
+    }
+
+    // Mesh the chunks into plain vertex and index buffers, ready for any renderer.
+    let from = ChunkPos::new(-1, -1, -1);
+    let to = ChunkPos::new(1, 1, 1);
+    for algorithm in [MeshAlgorithm::Cubes, MeshAlgorithm::SurfaceNets] {
+        let buffers = world.export_buffers(from, to, algorithm);
+        println!(
+            "{algorithm:?}: {} vertices, {} triangles",
+            buffers.positions.len(),
+            buffers.indices.len() / 3
+        );
+    }
+}
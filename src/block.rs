@@ -0,0 +1,143 @@
+#[cfg(feature = "bevy")]
+use bevy::ecs::system::Resource;
+
+use crate::{fluid::SOURCE_LEVEL, light::Emission, mesh::Voxel, util::Color};
+
+/// Sand, generated along the shores.
+pub const SAND: Voxel = Voxel::Opaque(Color::new(242, 231, 122));
+/// Gravel, falling like sand.
+pub const GRAVEL: Voxel = Voxel::Opaque(Color::new(136, 126, 122));
+/// Stone, generated on the slopes.
+pub const STONE: Voxel = Voxel::Opaque(Color::new(146, 142, 133));
+/// Snow, generated on the peaks.
+pub const SNOW: Voxel = Voxel::Opaque(Color::new(255, 250, 250));
+/// A lamp, lighting up its surroundings in warm white.
+pub const LAMP: Voxel = Voxel::Opaque(Color::new(255, 214, 140));
+/// Lava, glowing orange.
+pub const LAVA: Voxel = Voxel::Opaque(Color::new(226, 88, 34));
+/// A crystal, glowing blue.
+pub const CRYSTAL: Voxel = Voxel::Opaque(Color::new(96, 156, 255));
+/// A water source, filling the oceans.
+pub const WATER: Voxel = Voxel::Liquid(Color::new(52, 110, 196), SOURCE_LEVEL);
+
+// Public struct `FaceTextures` holding the asset paths of the textures for the faces of a block.
+#[derive(Clone, Debug)]
+pub struct FaceTextures {
+    // The texture of the face pointing up.
+    pub top: String,
+    // The texture of the four faces pointing sideways.
+    pub side: String,
+    // The texture of the face pointing down.
+    pub bottom: String,
+}
+
+impl FaceTextures {
+    // Public method `all` uses the same texture for every face.
+    pub fn all(path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            top: path.clone(),
+            side: path.clone(),
+            bottom: path,
+        }
+    }
+
+    // Public method `paths` returns the texture paths ordered by side index.
+    pub fn paths(&self) -> [&str; 6] {
+        [
+            &self.side,
+            &self.side,
+            &self.bottom,
+            &self.top,
+            &self.side,
+            &self.side,
+        ]
+    }
+}
+
+// Public struct `BlockDefinition` describing a block that can be placed in the world.
+#[derive(Clone, Debug)]
+pub struct BlockDefinition {
+    // The name of the block.
+    pub name: String,
+    // The voxel the block is stored as.
+    pub voxel: Voxel,
+    // The textures of the block, blocks without textures only use their color.
+    pub textures: Option<FaceTextures>,
+    // The red, green and blue light levels the block emits, all 0 for blocks that don't glow.
+    pub emission: [u8; 3],
+    // Whether the block falls down when there is nothing below it.
+    pub falls: bool,
+}
+
+impl BlockDefinition {
+    // Public method `new` creates a color-only block.
+    pub fn new(name: impl Into<String>, voxel: Voxel) -> Self {
+        Self {
+            name: name.into(),
+            voxel,
+            textures: None,
+            emission: [0; 3],
+            falls: false,
+        }
+    }
+
+    // Public method `with_textures` sets the textures of the block.
+    pub fn with_textures(mut self, textures: FaceTextures) -> Self {
+        self.textures = Some(textures);
+        self
+    }
+
+    // Public method `with_emission` makes the block emit light of the given red, green and blue levels.
+    pub fn with_emission(mut self, emission: [u8; 3]) -> Self {
+        self.emission = emission;
+        self
+    }
+
+    // Public method `with_gravity` makes the block fall down when there is nothing below it.
+    pub fn with_gravity(mut self) -> Self {
+        self.falls = true;
+        self
+    }
+}
+
+// Public struct `BlockRegistry` holding the definitions of every known block.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct BlockRegistry(pub Vec<BlockDefinition>);
+
+impl BlockRegistry {
+    // Public method `get` returns the definition of the block stored as `voxel`.
+    pub fn get(&self, voxel: Voxel) -> Option<&BlockDefinition> {
+        self.0.iter().find(|block| block.voxel == voxel)
+    }
+
+    // Public method `falls` checks if the block stored as `voxel` falls down when there is nothing below it.
+    pub fn falls(&self, voxel: Voxel) -> bool {
+        self.get(voxel).is_some_and(|block| block.falls)
+    }
+
+    // Public method `emission` returns the light levels of every block that emits light.
+    pub fn emission(&self) -> Emission {
+        self.0
+            .iter()
+            .filter(|block| block.emission.iter().any(|level| *level > 0))
+            .map(|block| (block.voxel, block.emission))
+            .collect()
+    }
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        Self(vec![
+            BlockDefinition::new("sand", SAND).with_gravity(),
+            BlockDefinition::new("gravel", GRAVEL).with_gravity(),
+            BlockDefinition::new("stone", STONE),
+            BlockDefinition::new("snow", SNOW),
+            BlockDefinition::new("lamp", LAMP).with_emission([14, 13, 11]),
+            BlockDefinition::new("lava", LAVA).with_emission([14, 7, 2]),
+            BlockDefinition::new("crystal", CRYSTAL).with_emission([4, 9, 14]),
+            BlockDefinition::new("water", WATER),
+        ])
+    }
+}
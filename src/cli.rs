@@ -0,0 +1,46 @@
+pub mod args;
+pub mod generate;
+
+pub use args::{
+    parse_position, parse_sea_level, parse_seed, region_chunks, GenerateArgs, MapArgs,
+    MAX_REGION_VOXELS, USAGE,
+};
+pub use generate::{generate, map, GenerationStats};
+
+/// Public function `run` runs the subcommand named by the command line arguments, without opening a window,
+/// and returns the exit code of the process. Returns `None` when no subcommand is given, so the game starts instead.
+pub fn run(mut args: impl Iterator<Item = String>) -> Option<i32> {
+    let command = args.next()?;
+    let code = match command.as_str() {
+        "generate" => match GenerateArgs::parse(args).and_then(|args| generate(&args)) {
+            Ok(stats) => {
+                print!("{stats}");
+                0
+            }
+            Err(error) => {
+                eprintln!("error: {error}\n\n{USAGE}");
+                1
+            }
+        },
+        "map" => match MapArgs::parse(args).and_then(|args| map(&args)) {
+            Ok([width, height]) => {
+                println!("map: {width}x{height}");
+                0
+            }
+            Err(error) => {
+                eprintln!("error: {error}\n\n{USAGE}");
+                1
+            }
+        },
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            0
+        }
+        _ => {
+            eprintln!("error: unknown command `{command}`\n\n{USAGE}");
+            2
+        }
+    };
+
+    Some(code)
+}
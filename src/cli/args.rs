@@ -0,0 +1,269 @@
+use std::path::PathBuf;
+
+use crate::{
+    mesh::{GenerationSettings, MeshAlgorithm},
+    util::ChunkPos,
+    world::WorldConfig,
+};
+
+/// The help text of the command line.
+pub const USAGE: &str = "\
+usage: voxel [generate [options] | map <world> <png> [--seed <n>] [--sea-level <n>]]
+
+Without a command the game starts, if built with the `bevy` feature. The generate command
+generates, lights and meshes a region of chunks without opening a window and prints statistics
+about it.
+The map command renders a top-down map of every chunk of a saved world.
+
+options:
+  --seed <n>             seed of the terrain noise (default 1)
+  --sea-level <n>        height below which empty space is filled with water, in voxels
+  --algorithm <name>     mesher to use, `cubes` or `surface-nets` (default cubes)
+  --chunk-size <n>       voxels along each axis of a chunk, a multiple of 8 up to 256 (default 48)
+  --voxel-size <n>       size of a voxel in world units (default 0.1)
+  --from <x,y,z>         first corner of the region, in chunks (default -2,-1,-2)
+  --to <x,y,z>           second corner of the region, in chunks (default 2,1,2)
+  --save <path>          write the generated world to a file
+  --export <path>        write the mesh of the region to an .obj, .ply or .glb file
+  --map <path>           write a top-down map of the region to a .png file";
+
+/// The most voxels the generate command may hold in memory, counting the padding of every chunk, so oversized
+/// regions are rejected before anything is allocated.
+pub const MAX_REGION_VOXELS: u64 = 1 << 28;
+
+// Public struct `GenerateArgs` holding the options of the generate command.
+#[derive(Clone, Debug)]
+pub struct GenerateArgs {
+    pub generation: GenerationSettings,
+    pub config: WorldConfig,
+    pub algorithm: MeshAlgorithm,
+    // The corners of the region to generate, in chunk positions, both included.
+    pub from: ChunkPos,
+    pub to: ChunkPos,
+    pub save: Option<PathBuf>,
+    pub export: Option<PathBuf>,
+    pub map: Option<PathBuf>,
+}
+
+impl Default for GenerateArgs {
+    fn default() -> Self {
+        Self {
+            generation: GenerationSettings::default(),
+            config: WorldConfig::default(),
+            algorithm: MeshAlgorithm::default(),
+            from: ChunkPos::new(-2, -1, -2),
+            to: ChunkPos::new(2, 1, 2),
+            save: None,
+            export: None,
+            map: None,
+        }
+    }
+}
+
+// Public function `parse_position` parses a position written as `x,y,z`.
+pub fn parse_position(text: &str) -> Result<ChunkPos, String> {
+    let axes: Vec<i32> = text
+        .split(',')
+        .map(|axis| axis.trim().parse::<i32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid position `{text}`, expected x,y,z"))?;
+
+    match axes[..] {
+        [x, y, z] => Ok(ChunkPos::new(x, y, z)),
+        _ => Err(format!("invalid position `{text}`, expected x,y,z")),
+    }
+}
+
+// Public function `parse_seed` parses the seed of the terrain noise, rejecting anything that doesn't fit a `u32`
+// instead of wrapping it around.
+pub fn parse_seed(text: &str) -> Result<u32, String> {
+    text.trim().parse().map_err(|_| {
+        format!(
+            "invalid seed `{text}`, expected a number from 0 to {}",
+            u32::MAX
+        )
+    })
+}
+
+// Public function `region_chunks` counts the chunks of the region between two corners, both included, rejecting
+// regions whose chunks hold more than `MAX_REGION_VOXELS` voxels together instead of running out of memory.
+pub fn region_chunks(from: ChunkPos, to: ChunkPos, config: &WorldConfig) -> Result<usize, String> {
+    let chunks = [
+        from.x.abs_diff(to.x),
+        from.y.abs_diff(to.y),
+        from.z.abs_diff(to.z),
+    ]
+    .into_iter()
+    .try_fold(1u64, |count, extent| {
+        count.checked_mul(u64::from(extent) + 1)
+    });
+    let voxels = chunks.and_then(|chunks| chunks.checked_mul((config.padded() as u64).pow(3)));
+
+    match (chunks, voxels) {
+        (Some(chunks), Some(voxels)) if voxels <= MAX_REGION_VOXELS => Ok(chunks as usize),
+        _ => Err(format!(
+            "region from {},{},{} to {},{},{} is too large, expected at most {MAX_REGION_VOXELS} voxels",
+            from.x, from.y, from.z, to.x, to.y, to.z
+        )),
+    }
+}
+
+// Public function `parse_sea_level` parses the sea level, in voxels.
+pub fn parse_sea_level(text: &str) -> Result<i32, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("invalid sea level `{text}`"))
+}
+
+impl GenerateArgs {
+    // Public method `parse` reads the options of the generate command, starting from the defaults.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let (mut chunk_size, mut voxel_size) = (parsed.config.chunk_size, parsed.config.voxel_size);
+
+        while let Some(option) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for `{option}`"))
+            };
+            match option.as_str() {
+                "--seed" => parsed.generation.seed = parse_seed(&value()?)?,
+                "--sea-level" => parsed.generation.sea_level = parse_sea_level(&value()?)?,
+                "--algorithm" => {
+                    parsed.algorithm = match value()?.as_str() {
+                        "cubes" => MeshAlgorithm::Cubes,
+                        "surface-nets" => MeshAlgorithm::SurfaceNets,
+                        other => return Err(format!("unknown algorithm `{other}`")),
+                    }
+                }
+                "--chunk-size" => {
+                    let value = value()?;
+                    chunk_size = value
+                        .parse()
+                        .map_err(|_| format!("invalid chunk size `{value}`"))?;
+                }
+                "--voxel-size" => {
+                    let value = value()?;
+                    voxel_size = value
+                        .parse()
+                        .map_err(|_| format!("invalid voxel size `{value}`"))?;
+                }
+                "--from" => parsed.from = parse_position(&value()?)?,
+                "--to" => parsed.to = parse_position(&value()?)?,
+                "--save" => parsed.save = Some(value()?.into()),
+                "--export" => parsed.export = Some(value()?.into()),
+                "--map" => parsed.map = Some(value()?.into()),
+                _ => return Err(format!("unknown option `{option}`")),
+            }
+        }
+        parsed.config = WorldConfig::try_new(chunk_size, voxel_size)?;
+        region_chunks(parsed.from, parsed.to, &parsed.config)?;
+
+        Ok(parsed)
+    }
+}
+
+// Public struct `MapArgs` holding the options of the map command.
+#[derive(Clone, Debug)]
+pub struct MapArgs {
+    pub world: PathBuf,
+    pub png: PathBuf,
+    // The settings the world was generated with, used to generate the padding of its chunks when loading.
+    pub generation: GenerationSettings,
+}
+
+impl MapArgs {
+    // Public method `parse` reads the paths and options of the map command.
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut paths = Vec::new();
+        let mut generation = GenerationSettings::default();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for `{arg}`"))
+            };
+            match arg.as_str() {
+                "--seed" => generation.seed = parse_seed(&value()?)?,
+                "--sea-level" => generation.sea_level = parse_sea_level(&value()?)?,
+                option if option.starts_with("--") => {
+                    return Err(format!("unknown option `{option}`"))
+                }
+                path => paths.push(PathBuf::from(path)),
+            }
+        }
+
+        match <[PathBuf; 2]>::try_from(paths) {
+            Ok([world, png]) => Ok(Self {
+                world,
+                png,
+                generation,
+            }),
+            Err(_) => Err("expected a world and a png path".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn seeds_must_fit_a_u32() {
+        assert_eq!(parse_seed("0"), Ok(0));
+        assert_eq!(parse_seed("4294967295"), Ok(u32::MAX));
+        assert!(parse_seed("4294967296").is_err());
+        assert!(parse_seed("-1").is_err());
+        assert!(parse_seed("seed").is_err());
+    }
+
+    #[test]
+    fn commands_share_the_seed_parser() {
+        let generate = GenerateArgs::parse(args("--seed 7 --sea-level -3")).unwrap();
+        let map = MapArgs::parse(args("world.bin map.png --seed 7 --sea-level -3")).unwrap();
+        assert_eq!(generate.generation.seed, 7);
+        assert_eq!(map.generation.seed, 7);
+        assert_eq!(generate.generation.sea_level, -3);
+        assert_eq!(map.generation.sea_level, -3);
+
+        // Seeds that used to wrap around are rejected by both commands.
+        assert!(GenerateArgs::parse(args("--seed 4294967296")).is_err());
+        assert!(MapArgs::parse(args("world.bin map.png --seed 4294967296")).is_err());
+        assert!(MapArgs::parse(args("world.bin map.png --sea-level 2147483648")).is_err());
+    }
+
+    #[test]
+    fn oversized_regions_are_rejected() {
+        let config = WorldConfig::new(8, 1.0);
+        let origin = ChunkPos::new(0, 0, 0);
+        assert_eq!(region_chunks(origin, origin, &config), Ok(1));
+        assert_eq!(
+            region_chunks(ChunkPos::new(2, 1, 2), ChunkPos::new(-2, -1, -2), &config),
+            Ok(75)
+        );
+
+        // Corners far enough apart to overflow the count are rejected rather than wrapped around.
+        let (min, max) = (
+            ChunkPos::new(i32::MIN, i32::MIN, i32::MIN),
+            ChunkPos::new(i32::MAX, i32::MAX, i32::MAX),
+        );
+        assert!(region_chunks(min, max, &config).is_err());
+        assert!(region_chunks(origin, ChunkPos::new(1000, 1000, 1000), &config).is_err());
+
+        assert!(GenerateArgs::parse(args("--from -2,-1,-2 --to 2,1,2")).is_ok());
+        assert!(GenerateArgs::parse(args("--from 0,0,0 --to 100,100,100")).is_err());
+        // The limit counts voxels, so larger chunks allow fewer of them.
+        assert!(GenerateArgs::parse(args("--chunk-size 256 --from 0,0,0 --to 3,3,3")).is_err());
+    }
+
+    #[test]
+    fn positions_have_three_axes() {
+        assert_eq!(parse_position("1, -2,3"), Ok(ChunkPos::new(1, -2, 3)));
+        assert!(parse_position("1,2").is_err());
+        assert!(parse_position("1,2,3,4").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    block::BlockRegistry,
+    map::MAX_MAP_PIXELS,
+    mesh::{ChunkMesh, MeshAlgorithm, Voxel},
+    util::ChunkPos,
+    world::VoxelWorld,
+};
+
+use super::{region_chunks, GenerateArgs, MapArgs};
+
+// Public struct `GenerationStats` describing a generated region and how long each step took.
+#[derive(Clone, Debug, Default)]
+pub struct GenerationStats {
+    pub chunks: usize,
+    // The number of voxels of every block, by name, with unknown voxels named by their debug output.
+    pub voxels: Vec<(String, usize)>,
+    pub quads: usize,
+    pub vertices: usize,
+    pub triangles: usize,
+    // The time spent generating and lighting the chunks on their own.
+    pub generation: Duration,
+    // The time spent adding the chunks to the world, merging their light.
+    pub merging: Duration,
+    pub meshing: Duration,
+}
+
+impl fmt::Display for GenerationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "chunks:     {}", self.chunks)?;
+        writeln!(f, "quads:      {}", self.quads)?;
+        writeln!(f, "vertices:   {}", self.vertices)?;
+        writeln!(f, "triangles:  {}", self.triangles)?;
+        writeln!(f, "generation: {:.3}s", self.generation.as_secs_f64())?;
+        writeln!(f, "merging:    {:.3}s", self.merging.as_secs_f64())?;
+        writeln!(f, "meshing:    {:.3}s", self.meshing.as_secs_f64())?;
+        writeln!(f, "voxels:")?;
+        for (name, count) in self.voxels.iter() {
+            writeln!(f, "  {name:<12}{count}")?;
+        }
+        Ok(())
+    }
+}
+
+// Function `map_too_large` creates the error returned when a map has too many pixels to render.
+fn map_too_large(path: &Path) -> String {
+    format!(
+        "could not write the map to {}: it would have more than {MAX_MAP_PIXELS} pixels",
+        path.display()
+    )
+}
+
+/// Public function `generate` generates, lights and meshes every chunk of a region without rendering anything,
+/// then saves the world and exports the mesh of the region if requested.
+pub fn generate(args: &GenerateArgs) -> Result<GenerationStats, String> {
+    let registry = BlockRegistry::default();
+    let emission = registry.emission();
+    let mut stats = GenerationStats::default();
+
+    let (from, to) = (args.from, args.to);
+    let mut positions = Vec::with_capacity(region_chunks(from, to, &args.config)?);
+    for z in from.z.min(to.z)..=from.z.max(to.z) {
+        for y in from.y.min(to.y)..=from.y.max(to.y) {
+            for x in from.x.min(to.x)..=from.x.max(to.x) {
+                positions.push(ChunkPos::new(x, y, z));
+            }
+        }
+    }
+    stats.chunks = positions.len();
+
+    let start = Instant::now();
+    let chunks: Vec<ChunkMesh> = positions
+        .into_par_iter()
+        .map(|position| {
+            let mut chunk = ChunkMesh::new(position, args.config);
+            chunk.populate(&args.generation);
+            chunk.compute_light(&emission);
+            chunk
+        })
+        .collect();
+    stats.generation = start.elapsed();
+
+    let start = Instant::now();
+    let mut world = VoxelWorld::new(args.config);
+    for chunk in chunks {
+        world.insert(chunk, &emission);
+    }
+    world.dirty.clear();
+    stats.merging = start.elapsed();
+
+    // Count the voxels inside every chunk, leaving out the padding shared with the neighbors.
+    let size = world.config.chunk_size;
+    let chunks: Vec<&ChunkMesh> = world.chunks.values().collect();
+    let counts = chunks
+        .par_iter()
+        .map(|chunk| {
+            let mut counts: HashMap<Voxel, usize> = HashMap::new();
+            for z in 1..=size {
+                for y in 1..=size {
+                    for x in 1..=size {
+                        *counts.entry(chunk.get(x, y, z)).or_default() += 1;
+                    }
+                }
+            }
+            counts
+        })
+        .reduce(HashMap::new, |mut a, b| {
+            for (voxel, count) in b {
+                *a.entry(voxel).or_default() += count;
+            }
+            a
+        });
+    stats.voxels = counts
+        .into_iter()
+        .map(|(voxel, count)| {
+            let name = match (voxel, registry.get(voxel)) {
+                (Voxel::Empty, _) => "air".to_string(),
+                (_, Some(block)) => block.name.clone(),
+                (voxel, None) => format!("{voxel:?}"),
+            };
+            (name, count)
+        })
+        .collect();
+    stats
+        .voxels
+        .sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+    let start = Instant::now();
+    let buffers = world.export_buffers(from, to, args.algorithm);
+    stats.meshing = start.elapsed();
+    stats.vertices = buffers.positions.len();
+    stats.triangles = buffers.indices.len() / 3;
+    stats.quads = match args.algorithm {
+        MeshAlgorithm::Cubes => buffers.positions.len() / 4,
+        MeshAlgorithm::SurfaceNets => buffers.indices.len() / 6,
+    };
+
+    if let Some(path) = args.save.as_ref() {
+        world
+            .save(path)
+            .map_err(|error| format!("could not save the world to {}: {error}", path.display()))?;
+    }
+    if let Some(path) = args.map.as_ref() {
+        let [min, max] = world.config.voxel_bounds(from, to);
+        world
+            .render_map([min.x, min.z], [max.x, max.z])
+            .ok_or_else(|| map_too_large(path))?
+            .save_png(path)
+            .map_err(|error| format!("could not write the map to {}: {error}", path.display()))?;
+    }
+    if let Some(path) = args.export.as_ref() {
+        buffers
+            .export(path)
+            .map_err(|error| format!("could not export the mesh to {}: {error}", path.display()))?;
+    }
+
+    Ok(stats)
+}
+
+// Public function `map` renders a top-down map of every chunk of a saved world and returns its size in pixels.
+pub fn map(args: &MapArgs) -> Result<[usize; 2], String> {
+    let emission = BlockRegistry::default().emission();
+    let world = VoxelWorld::load(&args.world, &args.generation, &emission).map_err(|error| {
+        format!(
+            "could not load the world from {}: {error}",
+            args.world.display()
+        )
+    })?;
+    let Some([min, max]) = world.bounds() else {
+        return Err("the world has no chunks".to_string());
+    };
+
+    let image = world
+        .render_map([min.x, min.z], [max.x, max.z])
+        .ok_or_else(|| map_too_large(&args.png))?;
+    image
+        .save_png(&args.png)
+        .map_err(|error| format!("could not write the map to {}: {error}", args.png.display()))?;
+
+    Ok([image.width, image.height])
+}
@@ -0,0 +1,7 @@
+pub mod format;
+pub mod gltf;
+pub mod obj;
+pub mod ply;
+pub mod region;
+
+pub use format::ExportFormat;
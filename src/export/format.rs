@@ -0,0 +1,53 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::mesh::MeshBuffers;
+
+// Public enum `ExportFormat` listing the file formats meshes can be exported to.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Obj,
+    Ply,
+    Glb,
+}
+
+impl ExportFormat {
+    // Public method `from_path` picks the format matching the extension of a file, if any.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "obj" => Some(Self::Obj),
+            "ply" => Some(Self::Ply),
+            "glb" => Some(Self::Glb),
+            _ => None,
+        }
+    }
+}
+
+impl MeshBuffers {
+    // Public method `write` writes the buffers in the given format.
+    pub fn write(&self, writer: &mut impl Write, format: ExportFormat) -> io::Result<()> {
+        match format {
+            ExportFormat::Obj => self.write_obj(writer),
+            ExportFormat::Ply => self.write_ply(writer),
+            ExportFormat::Glb => self.write_glb(writer),
+        }
+    }
+
+    // Public method `export` writes the buffers to a file, in the format matching its extension.
+    pub fn export(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let format = ExportFormat::from_path(&path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "unknown export format, expected .obj, .ply or .glb",
+            )
+        })?;
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()
+    }
+}
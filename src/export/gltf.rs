@@ -0,0 +1,120 @@
+use std::io::{self, Write};
+
+use crate::mesh::MeshBuffers;
+
+// The magic numbers of a binary glTF file and its chunks.
+const GLB_MAGIC: u32 = 0x4654_6c67;
+const JSON_CHUNK: u32 = 0x4e4f_534a;
+const BIN_CHUNK: u32 = 0x004e_4942;
+
+// The types of the buffer views, as used by the GPU.
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+// The component types of the accessors.
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+impl MeshBuffers {
+    /// Public method `write_glb` writes the buffers as a binary glTF 2.0 file holding a single mesh
+    /// with positions, normals, vertex colors and indices. Fails for empty buffers, which glTF can't describe.
+    pub fn write_glb(&self, writer: &mut impl Write) -> io::Result<()> {
+        if self.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot export an empty mesh to glTF",
+            ));
+        }
+
+        // Lay the attributes out one after the other in the binary chunk, each 4-byte aligned.
+        let mut bin = Vec::new();
+        let mut views = Vec::new();
+        let mut add_view = |bytes: Vec<u8>, target: u32| {
+            views.push((bin.len(), bytes.len(), target));
+            bin.extend(bytes);
+        };
+        let floats = |values: &mut dyn Iterator<Item = f32>| -> Vec<u8> {
+            values.flat_map(f32::to_le_bytes).collect()
+        };
+        add_view(
+            floats(&mut self.positions.iter().flatten().copied()),
+            ARRAY_BUFFER,
+        );
+        add_view(
+            floats(&mut self.normals.iter().flatten().copied()),
+            ARRAY_BUFFER,
+        );
+        add_view(
+            floats(&mut self.colors.iter().flatten().copied()),
+            ARRAY_BUFFER,
+        );
+        add_view(
+            self.indices
+                .iter()
+                .flat_map(|index| index.to_le_bytes())
+                .collect(),
+            ELEMENT_ARRAY_BUFFER,
+        );
+
+        // Positions need their bounds.
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for position in self.positions.iter() {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(position[axis]);
+                max[axis] = max[axis].max(position[axis]);
+            }
+        }
+
+        let vertices = self.positions.len();
+        let views_json: Vec<String> = views
+            .iter()
+            .map(|(offset, length, target)| {
+                format!(
+                    r#"{{"buffer":0,"byteOffset":{offset},"byteLength":{length},"target":{target}}}"#
+                )
+            })
+            .collect();
+        let json = format!(
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"voxel"}},"#,
+                r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1,"COLOR_0":2}},"indices":3,"mode":4}}]}}],"#,
+                r#""accessors":["#,
+                r#"{{"bufferView":0,"componentType":{float},"count":{vertices},"type":"VEC3","min":[{min0},{min1},{min2}],"max":[{max0},{max1},{max2}]}},"#,
+                r#"{{"bufferView":1,"componentType":{float},"count":{vertices},"type":"VEC3"}},"#,
+                r#"{{"bufferView":2,"componentType":{float},"count":{vertices},"type":"VEC4"}},"#,
+                r#"{{"bufferView":3,"componentType":{uint},"count":{indices},"type":"SCALAR"}}],"#,
+                r#""bufferViews":[{views}],"buffers":[{{"byteLength":{length}}}]}}"#,
+            ),
+            float = FLOAT,
+            uint = UNSIGNED_INT,
+            vertices = vertices,
+            indices = self.indices.len(),
+            min0 = min[0],
+            min1 = min[1],
+            min2 = min[2],
+            max0 = max[0],
+            max1 = max[1],
+            max2 = max[2],
+            views = views_json.join(","),
+            length = bin.len(),
+        );
+
+        // Both chunks are padded to 4 bytes, the JSON with spaces and the binary data with zeros.
+        let mut json = json.into_bytes();
+        json.resize(json.len().next_multiple_of(4), b' ');
+        bin.resize(bin.len().next_multiple_of(4), 0);
+
+        let length = 12 + 8 + json.len() + 8 + bin.len();
+        writer.write_all(&GLB_MAGIC.to_le_bytes())?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&(length as u32).to_le_bytes())?;
+        writer.write_all(&(json.len() as u32).to_le_bytes())?;
+        writer.write_all(&JSON_CHUNK.to_le_bytes())?;
+        writer.write_all(&json)?;
+        writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+        writer.write_all(&BIN_CHUNK.to_le_bytes())?;
+        writer.write_all(&bin)
+    }
+}
@@ -0,0 +1,24 @@
+use std::io::{self, Write};
+
+use crate::mesh::MeshBuffers;
+
+impl MeshBuffers {
+    /// Public method `write_obj` writes the buffers as a Wavefront OBJ file.
+    /// Vertex colors follow the position of every vertex, an extension most tools understand.
+    pub fn write_obj(&self, writer: &mut impl Write) -> io::Result<()> {
+        for ([x, y, z], [red, green, blue, _]) in self.positions.iter().zip(self.colors.iter()) {
+            writeln!(writer, "v {x} {y} {z} {red} {green} {blue}")?;
+        }
+        for [x, y, z] in self.normals.iter() {
+            writeln!(writer, "vn {x} {y} {z}")?;
+        }
+
+        // Indices are 1-based, and every vertex has a normal of the same index.
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+            writeln!(writer, "f {a}//{a} {b}//{b} {c}//{c}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use std::io::{self, Write};
+
+use crate::mesh::MeshBuffers;
+
+// Function `byte` converts a color channel between 0 and 1 to a byte.
+fn byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl MeshBuffers {
+    // Public method `write_ply` writes the buffers as an ASCII PLY file with normals and byte vertex colors.
+    pub fn write_ply(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "element vertex {}", self.positions.len())?;
+        for property in ["x", "y", "z", "nx", "ny", "nz"] {
+            writeln!(writer, "property float {property}")?;
+        }
+        for property in ["red", "green", "blue", "alpha"] {
+            writeln!(writer, "property uchar {property}")?;
+        }
+        writeln!(writer, "element face {}", self.indices.len() / 3)?;
+        writeln!(writer, "property list uchar uint vertex_indices")?;
+        writeln!(writer, "end_header")?;
+
+        for ((position, normal), color) in self
+            .positions
+            .iter()
+            .zip(self.normals.iter())
+            .zip(self.colors.iter())
+        {
+            let [x, y, z] = position;
+            let [nx, ny, nz] = normal;
+            let [red, green, blue, alpha] = color.map(byte);
+            writeln!(
+                writer,
+                "{x} {y} {z} {nx} {ny} {nz} {red} {green} {blue} {alpha}"
+            )?;
+        }
+        for triangle in self.indices.chunks_exact(3) {
+            writeln!(writer, "3 {} {} {}", triangle[0], triangle[1], triangle[2])?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    mesh::{ChunkMesh, Face, MeshAlgorithm, MeshBuffers},
+    util::ChunkPos,
+    world::VoxelWorld,
+};
+
+impl ChunkMesh {
+    /// Public method `export_buffers` meshes the chunk at full resolution without any textures,
+    /// with positions relative to the center of its first voxel, where `WorldConfig::chunk_translation` puts it.
+    /// The faces of that voxel lie half a voxel around the origin, so the mesh starts at `-voxel_size / 2`.
+    /// Faces are emitted in a fixed order, so meshing the same voxels always gives the same buffers.
+    pub fn export_buffers(&self, algorithm: MeshAlgorithm) -> MeshBuffers {
+        if algorithm == MeshAlgorithm::SurfaceNets {
+            return self.generate_surface_nets();
+        }
+
+        let mut groups = self.generate_mesh().groups;
+        let mut buffers = MeshBuffers::default();
+        for (index, quads) in groups.iter_mut().enumerate() {
+            quads.sort_by_key(|quad| quad.voxel);
+            for quad in quads.iter() {
+                let face = Face {
+                    side: index.into(),
+                    quad,
+                };
+                buffers.push_face(&face, 0, self.config.voxel_size);
+            }
+        }
+
+        buffers
+    }
+}
+
+impl VoxelWorld {
+    /// Public method `export_buffers` meshes every generated chunk between two chunk positions, both included,
+    /// into a single mesh in world space. Chunks are added in a fixed order, so the result is reproducible.
+    pub fn export_buffers(
+        &self,
+        a: ChunkPos,
+        b: ChunkPos,
+        algorithm: MeshAlgorithm,
+    ) -> MeshBuffers {
+        let mut buffers = MeshBuffers::default();
+
+        for z in a.z.min(b.z)..=a.z.max(b.z) {
+            for y in a.y.min(b.y)..=a.y.max(b.y) {
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    let Some(chunk) = self.chunks.get(&ChunkPos { x, y, z }) else {
+                        continue;
+                    };
+
+                    let offset = self.config.chunk_translation(ChunkPos { x, y, z });
+                    buffers.append(&chunk.export_buffers(algorithm), offset.to_array());
+                }
+            }
+        }
+
+        buffers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::STONE, light::Emission, mesh::Voxel, util::WorldVoxelPos, world::WorldConfig,
+    };
+
+    // Function `region` generates a world holding a solid region of 2×1×1 voxels at the origin.
+    fn region() -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        world.insert(chunk, &Emission::default());
+        world.set(WorldVoxelPos::new(0, 0, 0), STONE);
+        world.set(WorldVoxelPos::new(1, 0, 0), STONE);
+        world
+    }
+
+    fn export(write: impl Fn(&MeshBuffers, &mut Vec<u8>) -> std::io::Result<()>) -> String {
+        let world = region();
+        let origin = ChunkPos::new(0, 0, 0);
+        let buffers = world.export_buffers(origin, origin, MeshAlgorithm::Cubes);
+        let mut bytes = Vec::new();
+        write(&buffers, &mut bytes).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn chunk_buffers_start_half_a_voxel_before_the_origin() {
+        let config = WorldConfig::new(8, 0.25);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(1, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        chunk.set(1, 1, 1, STONE);
+
+        let buffers = chunk.export_buffers(MeshAlgorithm::Cubes);
+        let min = buffers
+            .positions
+            .iter()
+            .fold([f32::MAX; 3], |min, position| {
+                [0, 1, 2].map(|axis| min[axis].min(position[axis]))
+            });
+        assert_eq!(min, [-0.125; 3]);
+    }
+
+    #[test]
+    fn obj_snapshot() {
+        let obj = export(|buffers, bytes| buffers.write_obj(bytes));
+        assert_eq!(obj, include_str!("snapshots/two_voxels.obj"));
+    }
+
+    #[test]
+    fn ply_snapshot() {
+        let ply = export(|buffers, bytes| buffers.write_ply(bytes));
+        assert_eq!(ply, include_str!("snapshots/two_voxels.ply"));
+    }
+}
@@ -0,0 +1,19 @@
+pub mod block;
+
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::schedule::IntoSystemConfigs,
+};
+pub use block::{falling_block_handler, landing_handler, FallingBlock, FALL_DELAY};
+
+use crate::{physics::physics_handler, world::block_update_handler};
+
+pub struct FallingPlugin;
+
+impl Plugin for FallingPlugin {
+    fn build(&self, app: &mut App) {
+        // Blocks start falling on the tick they are updated and land right after the physics step.
+        app.add_systems(Update, falling_block_handler.after(block_update_handler))
+            .add_systems(Update, landing_handler.after(physics_handler));
+    }
+}
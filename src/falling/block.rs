@@ -0,0 +1,301 @@
+use bevy::{
+    asset::Assets,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        system::{Commands, Query, Res, ResMut},
+    },
+    math::Vec3,
+    pbr::{PbrBundle, StandardMaterial},
+    render::mesh::{shape, Mesh},
+    transform::components::Transform,
+};
+
+use crate::{
+    block::BlockRegistry,
+    mesh::Voxel,
+    physics::Body,
+    world::{BlockUpdate, BlockUpdateEvent, VoxelWorld},
+};
+
+/// The number of block ticks a block waits after something changed around it before it falls.
+pub const FALL_DELAY: u64 = 2;
+
+// The half extent of the box of a falling block in voxels, slightly smaller than a voxel so it fits through one voxel wide gaps.
+const HALF_EXTENT: f32 = 0.45;
+
+// How many voxels above its landing spot a block looks for room, when something took its place while it fell.
+const LANDING_SEARCH: i32 = 4;
+
+// Public struct `FallingBlock` marking an entity as a block that left the voxel grid to fall down.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct FallingBlock {
+    pub voxel: Voxel,
+}
+
+// Function `falls_into` checks if a falling block can move into or land in a voxel.
+fn falls_into(voxel: Option<Voxel>) -> bool {
+    matches!(voxel, Some(Voxel::Empty | Voxel::Liquid(..)))
+}
+
+// This system schedules blocks that fall for an update when they or their neighbors change,
+// then turns the ones that have nothing below them into falling entities, moved by the physics module until they land.
+pub fn falling_block_handler(
+    mut commands: Commands,
+    mut events: EventReader<BlockUpdateEvent>,
+    mut world: ResMut<VoxelWorld>,
+    registry: Res<BlockRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for event in events.read() {
+        let position = event.position;
+        let Some(voxel) = world.get(position) else {
+            continue;
+        };
+        if !registry.falls(voxel) {
+            continue;
+        }
+        match event.update {
+            BlockUpdate::Changed | BlockUpdate::Neighbor(_) => {
+                world.schedule(position, FALL_DELAY);
+                continue;
+            }
+            BlockUpdate::Scheduled => {}
+            BlockUpdate::Random => continue,
+        }
+        if !falls_into(world.get(position.offset([0, -1, 0]))) {
+            continue;
+        }
+
+        world.set(position, Voxel::Empty);
+
+        let translation = world.config.voxel_center(position);
+        let (size, half_extent) = (
+            world.config.voxel_size,
+            world.config.voxel_size * HALF_EXTENT,
+        );
+        commands.spawn((
+            FallingBlock { voxel },
+            Body::new(Vec3::splat(-half_extent), Vec3::splat(half_extent)),
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Cube { size })),
+                material: materials.add(StandardMaterial::from(bevy::render::color::Color::from(
+                    voxel.color().unwrap_or_default(),
+                ))),
+                transform: Transform::from_translation(translation),
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+// This system puts falling blocks that landed back into the voxel grid, replacing any liquid there.
+// Blocks that land in chunks that aren't generated wait for them, blocks that find no room near where they landed are dropped.
+pub fn landing_handler(
+    mut commands: Commands,
+    mut world: ResMut<VoxelWorld>,
+    blocks: Query<(Entity, &Transform, &Body, &FallingBlock)>,
+) {
+    for (entity, transform, body, block) in blocks.iter() {
+        if !body.grounded {
+            continue;
+        }
+
+        let landed = world.config.voxel_at(transform.translation);
+        if world.get(landed).is_none() {
+            continue;
+        }
+        if let Some(position) = (0..=LANDING_SEARCH)
+            .map(|dy| landed.offset([0, dy, 0]))
+            .find(|position| falls_into(world.get(*position)))
+        {
+            world.set(position, block.voxel);
+        }
+
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::{event::Events, system::RunSystemOnce, world::World};
+
+    use super::*;
+    use crate::{
+        block::{SAND, STONE, WATER},
+        light::Emission,
+        mesh::ChunkMesh,
+        util::{ChunkPos, WorldVoxelPos},
+        world::WorldConfig,
+    };
+
+    // Function `app` creates a world holding a single empty chunk of 8 voxels, along with the resources
+    // the falling block systems need.
+    fn app() -> World {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        world.insert(chunk, &Emission::default());
+
+        let mut app = World::new();
+        app.insert_resource(world);
+        app.insert_resource(BlockRegistry::default());
+        app.init_resource::<Assets<Mesh>>();
+        app.init_resource::<Assets<StandardMaterial>>();
+        app.init_resource::<Events<BlockUpdateEvent>>();
+        app
+    }
+
+    // Function `update` sends a single update of a voxel to the falling block handler.
+    fn update(app: &mut World, position: WorldVoxelPos, update: BlockUpdate) {
+        app.resource_mut::<Events<BlockUpdateEvent>>().clear();
+        app.send_event(BlockUpdateEvent { position, update });
+        app.run_system_once(falling_block_handler);
+    }
+
+    // Function `set` changes the voxels at world voxel positions.
+    fn set(app: &mut World, voxels: &[([i32; 3], Voxel)]) {
+        let mut world = app.resource_mut::<VoxelWorld>();
+        for (position, voxel) in voxels {
+            world.set_quietly((*position).into(), *voxel);
+        }
+    }
+
+    fn get(app: &World, position: [i32; 3]) -> Option<Voxel> {
+        app.resource::<VoxelWorld>().get(position.into())
+    }
+
+    // Function `land` spawns a grounded falling block in the middle of a voxel and runs the landing handler.
+    fn land(app: &mut World, position: [i32; 3]) -> Entity {
+        let translation = app
+            .resource::<VoxelWorld>()
+            .config
+            .voxel_center(position.into());
+        let mut body = Body::new(Vec3::splat(-HALF_EXTENT), Vec3::splat(HALF_EXTENT));
+        body.grounded = true;
+        let entity = app
+            .spawn((
+                FallingBlock { voxel: SAND },
+                body,
+                Transform::from_translation(translation),
+            ))
+            .id();
+        app.run_system_once(landing_handler);
+        entity
+    }
+
+    fn falling(app: &mut World) -> usize {
+        app.query::<&FallingBlock>().iter(app).count()
+    }
+
+    #[test]
+    fn blocks_fall_once_their_update_is_due() {
+        let mut app = app();
+        set(
+            &mut app,
+            &[([3, 4, 3], SAND), ([5, 1, 5], SAND), ([5, 0, 5], STONE)],
+        );
+
+        // Changes only schedule the block, which falls on the scheduled update.
+        update(&mut app, WorldVoxelPos::new(3, 4, 3), BlockUpdate::Changed);
+        assert_eq!(falling(&mut app), 0);
+        let world = app.resource::<VoxelWorld>();
+        let due = world.chunks[&ChunkPos::new(0, 0, 0)]
+            .updates
+            .values()
+            .copied();
+        assert_eq!(due.collect::<Vec<_>>(), [world.tick + FALL_DELAY]);
+
+        update(
+            &mut app,
+            WorldVoxelPos::new(3, 4, 3),
+            BlockUpdate::Scheduled,
+        );
+        assert_eq!(get(&app, [3, 4, 3]), Some(Voxel::Empty));
+        let blocks: Vec<(FallingBlock, Transform)> = app
+            .query::<(&FallingBlock, &Transform)>()
+            .iter(&app)
+            .map(|(block, transform)| (*block, *transform))
+            .collect();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].0.voxel, SAND);
+        assert_eq!(blocks[0].1.translation, Vec3::new(3.0, 4.0, 3.0));
+
+        // Blocks resting on the ground stay put.
+        update(
+            &mut app,
+            WorldVoxelPos::new(5, 1, 5),
+            BlockUpdate::Scheduled,
+        );
+        assert_eq!(get(&app, [5, 1, 5]), Some(SAND));
+        assert_eq!(falling(&mut app), 1);
+    }
+
+    #[test]
+    fn blocks_fall_through_liquid() {
+        let mut app = app();
+        set(&mut app, &[([3, 4, 3], SAND), ([3, 3, 3], WATER)]);
+        update(
+            &mut app,
+            WorldVoxelPos::new(3, 4, 3),
+            BlockUpdate::Scheduled,
+        );
+        assert_eq!(get(&app, [3, 4, 3]), Some(Voxel::Empty));
+        assert_eq!(falling(&mut app), 1);
+    }
+
+    #[test]
+    fn blocks_above_chunks_that_arent_generated_dont_fall() {
+        let mut app = app();
+        set(&mut app, &[([3, 0, 3], SAND)]);
+        update(
+            &mut app,
+            WorldVoxelPos::new(3, 0, 3),
+            BlockUpdate::Scheduled,
+        );
+        assert_eq!(get(&app, [3, 0, 3]), Some(SAND));
+        assert_eq!(falling(&mut app), 0);
+    }
+
+    #[test]
+    fn landed_blocks_return_to_the_grid() {
+        // On the ground.
+        let mut app = app();
+        set(&mut app, &[([3, 0, 3], STONE)]);
+        land(&mut app, [3, 1, 3]);
+        assert_eq!(get(&app, [3, 1, 3]), Some(SAND));
+        assert_eq!(falling(&mut app), 0);
+
+        // Into liquid, which the block replaces.
+        set(&mut app, &[([5, 1, 5], WATER)]);
+        land(&mut app, [5, 1, 5]);
+        assert_eq!(get(&app, [5, 1, 5]), Some(SAND));
+
+        // Onto something that took its place while it fell, finding room above it.
+        set(&mut app, &[([1, 1, 1], STONE), ([1, 2, 1], STONE)]);
+        land(&mut app, [1, 1, 1]);
+        assert_eq!(get(&app, [1, 3, 1]), Some(SAND));
+        assert_eq!(falling(&mut app), 0);
+    }
+
+    #[test]
+    fn blocks_landing_in_chunks_that_arent_generated_wait() {
+        let mut app = app();
+        let entity = land(&mut app, [3, -2, 3]);
+        assert!(app.get_entity(entity).is_some());
+        assert_eq!(get(&app, [3, -2, 3]), None);
+
+        // Once the chunk is generated the block lands.
+        let config = app.resource::<VoxelWorld>().config;
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, -1, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        app.resource_mut::<VoxelWorld>()
+            .insert(chunk, &Emission::default());
+        app.run_system_once(landing_handler);
+        assert!(app.get_entity(entity).is_none());
+        assert_eq!(get(&app, [3, -2, 3]), Some(SAND));
+    }
+}
@@ -0,0 +1,29 @@
+pub mod simulation;
+
+#[cfg(feature = "bevy")]
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::schedule::IntoSystemConfigs,
+};
+#[cfg(feature = "bevy")]
+pub use simulation::{fluid_handler, Fluids};
+pub use simulation::{FLUID_TICK, MAX_FLUID_UPDATES, SEA_LEVEL, SOURCE_LEVEL};
+
+#[cfg(feature = "bevy")]
+use crate::{light::light_handler, world::block_update_handler};
+
+#[cfg(feature = "bevy")]
+pub struct FluidPlugin;
+
+#[cfg(feature = "bevy")]
+impl Plugin for FluidPlugin {
+    fn build(&self, app: &mut App) {
+        // Fluid changes are relit and remeshed in the same frame.
+        app.init_resource::<Fluids>().add_systems(
+            Update,
+            fluid_handler
+                .after(block_update_handler)
+                .before(light_handler),
+        );
+    }
+}
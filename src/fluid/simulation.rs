@@ -0,0 +1,365 @@
+#[cfg(feature = "bevy")]
+use std::time::Duration;
+
+#[cfg(feature = "bevy")]
+use bevy::{
+    ecs::{
+        event::EventReader,
+        system::{Res, ResMut, Resource},
+    },
+    time::{Time, Timer, TimerMode},
+    utils::HashSet,
+};
+
+#[cfg(feature = "bevy")]
+use crate::{
+    mesh::LoadedChunks,
+    world::{BlockUpdate, BlockUpdateEvent},
+};
+use crate::{
+    mesh::{Side, Voxel},
+    util::WorldVoxelPos,
+    world::VoxelWorld,
+};
+
+/// The default height below which generation fills empty space with water, in voxels.
+pub const SEA_LEVEL: i32 = 1;
+
+/// The level of a liquid source, which never dries up.
+/// Falling liquid is one level lower, and every voxel liquid flows sideways lowers it by another level.
+pub const SOURCE_LEVEL: u8 = 8;
+
+/// The time between fluid updates, in seconds.
+pub const FLUID_TICK: f32 = 0.25;
+
+/// The most liquid voxels updated per tick, the rest wait for the next tick.
+pub const MAX_FLUID_UPDATES: usize = 4096;
+
+// The sides liquid flows sideways through.
+const HORIZONTAL: [usize; 4] = [0, 1, 4, 5];
+
+// Public struct `Fluids` holding the voxels to update on the next fluid tick.
+#[cfg(feature = "bevy")]
+#[derive(Resource)]
+pub struct Fluids {
+    pub active: HashSet<WorldVoxelPos>,
+    pub timer: Timer,
+}
+
+#[cfg(feature = "bevy")]
+impl Default for Fluids {
+    fn default() -> Self {
+        Self {
+            active: HashSet::new(),
+            timer: Timer::new(Duration::from_secs_f32(FLUID_TICK), TimerMode::Repeating),
+        }
+    }
+}
+
+impl VoxelWorld {
+    /// Public method `flow` updates the liquid at a world voxel position for one tick.
+    /// Liquid that isn't a source dries up unless liquid above or next to it feeds it, then the liquid
+    /// falls into the voxel below it, or spreads sideways one level lower if it rests on something.
+    /// Every voxel it changes is updated again on the next tick.
+    pub fn flow(&mut self, position: WorldVoxelPos) {
+        let Some(Voxel::Liquid(color, mut level)) = self.get(position) else {
+            return;
+        };
+        let same = |voxel: Option<Voxel>| match voxel {
+            Some(Voxel::Liquid(other, level)) if other == color => Some(level),
+            _ => None,
+        };
+
+        if level < SOURCE_LEVEL {
+            let fed = if same(self.get(position.offset([0, 1, 0]))).is_some() {
+                SOURCE_LEVEL - 1
+            } else {
+                HORIZONTAL
+                    .iter()
+                    .map(|index| position + Side::from(*index).offset())
+                    .filter(|neighbor| self.rests(*neighbor))
+                    .filter_map(|neighbor| same(self.get(neighbor)))
+                    .map(|level| level.saturating_sub(1))
+                    .max()
+                    .unwrap_or(0)
+            };
+
+            if fed == 0 {
+                self.set(position, Voxel::Empty);
+                return;
+            }
+            if fed != level {
+                self.set(position, Voxel::Liquid(color, fed));
+                level = fed;
+            }
+        }
+
+        // Fall into the voxel below if there is room.
+        let below = position.offset([0, -1, 0]);
+        match self.get(below) {
+            Some(Voxel::Empty) => {
+                self.set(below, Voxel::Liquid(color, SOURCE_LEVEL - 1));
+                return;
+            }
+            voxel => {
+                if same(voxel).is_some_and(|below| below < SOURCE_LEVEL - 1) {
+                    self.set(below, Voxel::Liquid(color, SOURCE_LEVEL - 1));
+                    return;
+                }
+            }
+        }
+
+        // Spread sideways when resting on something.
+        if !self.rests(position) || level <= 1 {
+            return;
+        }
+        for index in HORIZONTAL {
+            let neighbor = position + Side::from(index).offset();
+            let spread = match self.get(neighbor) {
+                Some(Voxel::Empty) => true,
+                voxel => same(voxel).is_some_and(|other| other < level - 1),
+            };
+            if spread {
+                self.set(neighbor, Voxel::Liquid(color, level - 1));
+            }
+        }
+    }
+
+    // Private method `rests` checks if a liquid at a world voxel position rests on something,
+    // being either solid ground or a liquid source, rather than falling.
+    fn rests(&self, position: WorldVoxelPos) -> bool {
+        match self.get(position.offset([0, -1, 0])) {
+            None | Some(Voxel::Empty) => false,
+            Some(Voxel::Liquid(_, level)) => level == SOURCE_LEVEL,
+            Some(_) => true,
+        }
+    }
+}
+
+// This system wakes up changed voxels and their neighbors and advances the liquid among them on every fluid tick.
+// Only liquid in loaded chunks is updated, so fluids in unloaded chunks stay where they are.
+#[cfg(feature = "bevy")]
+pub fn fluid_handler(
+    time: Res<Time>,
+    mut events: EventReader<BlockUpdateEvent>,
+    mut fluids: ResMut<Fluids>,
+    mut world: ResMut<VoxelWorld>,
+    loaded_chunks: Res<LoadedChunks>,
+) {
+    for event in events.read() {
+        if matches!(
+            event.update,
+            BlockUpdate::Changed | BlockUpdate::Neighbor(_)
+        ) {
+            fluids.active.insert(event.position);
+        }
+    }
+
+    fluids.timer.tick(time.delta());
+    if !fluids.timer.just_finished() {
+        return;
+    }
+
+    let batch: Vec<WorldVoxelPos> = fluids
+        .active
+        .iter()
+        .take(MAX_FLUID_UPDATES)
+        .copied()
+        .collect();
+
+    for position in batch {
+        fluids.active.remove(&position);
+
+        let chunk = position.chunk(world.config.chunk_size);
+        if !loaded_chunks.0.contains_key(&chunk) {
+            continue;
+        }
+        world.flow(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{
+        block::{STONE, WATER},
+        light::Emission,
+        mesh::ChunkMesh,
+        util::ChunkPos,
+        world::WorldConfig,
+    };
+
+    // Function `world` creates a single chunk of 8 voxels, empty above a floor of stone at y = 0.
+    fn world() -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = (0..chunk.size())
+            .map(|i| match chunk.delinearize(i) {
+                (_, 1, _) => STONE,
+                _ => Voxel::Empty,
+            })
+            .collect();
+        world.insert(chunk, &Emission::default());
+        world
+    }
+
+    // Function `settle` updates the changed voxels and their neighbors tick after tick, like the fluid handler does,
+    // until the liquid stops changing.
+    fn settle(world: &mut VoxelWorld) {
+        for _ in 0..256 {
+            let changed = std::mem::take(&mut world.changed);
+            if changed.is_empty() {
+                return;
+            }
+            let active: HashSet<WorldVoxelPos> = changed
+                .into_iter()
+                .flat_map(|position| std::iter::once(position).chain(position.neighbors()))
+                .collect();
+            for position in active {
+                world.flow(position);
+            }
+        }
+        panic!("the liquid didn't settle");
+    }
+
+    fn level(world: &VoxelWorld, position: [i32; 3]) -> Option<u8> {
+        match world.get(position.into()) {
+            Some(Voxel::Liquid(_, level)) => Some(level),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn liquid_falls_then_spreads_on_the_ground() {
+        let mut world = world();
+        world.set(WorldVoxelPos::new(3, 4, 3), WATER);
+
+        // A source above empty space feeds a column of falling liquid one level lower, which doesn't spread.
+        world.flow(WorldVoxelPos::new(3, 4, 3));
+        assert_eq!(level(&world, [3, 3, 3]), Some(SOURCE_LEVEL - 1));
+        assert_eq!(level(&world, [4, 4, 3]), None);
+        world.flow(WorldVoxelPos::new(3, 3, 3));
+        assert_eq!(level(&world, [3, 2, 3]), Some(SOURCE_LEVEL - 1));
+        assert_eq!(level(&world, [4, 3, 3]), None);
+
+        // Liquid resting on the ground spreads to its four sides one level lower.
+        world.flow(WorldVoxelPos::new(3, 2, 3));
+        world.flow(WorldVoxelPos::new(3, 1, 3));
+        assert_eq!(level(&world, [3, 1, 3]), Some(SOURCE_LEVEL - 1));
+        for position in [[2, 1, 3], [4, 1, 3], [3, 1, 2], [3, 1, 4]] {
+            assert_eq!(
+                level(&world, position),
+                Some(SOURCE_LEVEL - 2),
+                "{position:?}"
+            );
+        }
+        assert_eq!(level(&world, [4, 1, 4]), None);
+    }
+
+    #[test]
+    fn spreading_liquid_loses_a_level_per_voxel() {
+        let mut world = world();
+        world.set(WorldVoxelPos::new(0, 1, 0), WATER);
+        settle(&mut world);
+
+        for z in 0..8 {
+            for x in 0..8 {
+                let distance = (x + z) as u8;
+                let expected = (distance < SOURCE_LEVEL).then(|| SOURCE_LEVEL - distance);
+                assert_eq!(level(&world, [x, 1, z]), expected, "{x}, {z}");
+                assert_eq!(level(&world, [x, 2, z]), None, "{x}, {z}");
+            }
+        }
+    }
+
+    #[test]
+    fn liquid_dries_up_without_a_source() {
+        let mut world = world();
+        world.set(WorldVoxelPos::new(0, 1, 0), WATER);
+        settle(&mut world);
+
+        // Liquid that isn't a source is lowered to what its neighbors feed it, and dries up without any.
+        let color = WATER.color().unwrap();
+        world.set(WorldVoxelPos::new(6, 4, 6), Voxel::Liquid(color, 5));
+        world.flow(WorldVoxelPos::new(6, 4, 6));
+        assert_eq!(level(&world, [6, 4, 6]), None);
+
+        world.set(WorldVoxelPos::new(0, 1, 0), Voxel::Empty);
+        settle(&mut world);
+        for z in 0..8 {
+            for x in 0..8 {
+                assert_eq!(level(&world, [x, 1, z]), None, "{x}, {z}");
+            }
+        }
+    }
+
+    #[test]
+    fn liquid_stays_inside_generated_chunks() {
+        let mut world = world();
+        world.set(WorldVoxelPos::new(7, 1, 7), WATER);
+        settle(&mut world);
+
+        assert_eq!(level(&world, [6, 1, 7]), Some(SOURCE_LEVEL - 1));
+        assert_eq!(world.get(WorldVoxelPos::new(8, 1, 7)), None);
+        assert_eq!(world.get(WorldVoxelPos::new(7, 1, 8)), None);
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn updates_are_limited_per_tick() {
+        use bevy::{
+            ecs::{entity::Entity, event::Events, system::RunSystemOnce, world::World},
+            time::Time,
+        };
+
+        let mut app = World::new();
+        app.insert_resource(world());
+        app.insert_resource(Time::<()>::default());
+        app.insert_resource(LoadedChunks::default());
+        app.init_resource::<Fluids>();
+        app.init_resource::<Events<BlockUpdateEvent>>();
+        let tick = |app: &mut World, delta: f32| {
+            app.resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(delta));
+            app.run_system_once(fluid_handler);
+        };
+
+        // Changed voxels wake up and wait for the next fluid tick.
+        let source = WorldVoxelPos::new(3, 2, 3);
+        app.resource_mut::<VoxelWorld>().set_quietly(source, WATER);
+        app.send_event(BlockUpdateEvent {
+            position: source,
+            update: BlockUpdate::Changed,
+        });
+        let extra = (0..MAX_FLUID_UPDATES as i32 + 10).map(|y| WorldVoxelPos::new(100, y, 100));
+        app.resource_mut::<Fluids>().active.extend(extra);
+        tick(&mut app, 0.0);
+        assert_eq!(
+            app.resource::<Fluids>().active.len(),
+            MAX_FLUID_UPDATES + 11
+        );
+        app.resource_mut::<Events<BlockUpdateEvent>>().clear();
+
+        // Every tick updates at most the budget, the rest wait for the next tick.
+        tick(&mut app, FLUID_TICK);
+        assert_eq!(app.resource::<Fluids>().active.len(), 11);
+        tick(&mut app, FLUID_TICK);
+        assert!(app.resource::<Fluids>().active.is_empty());
+
+        // Liquid in chunks that aren't loaded doesn't flow.
+        assert_eq!(level(app.resource::<VoxelWorld>(), [3, 1, 3]), None);
+
+        app.resource_mut::<Fluids>().active.insert(source);
+        app.resource_mut::<LoadedChunks>()
+            .0
+            .insert(ChunkPos::new(0, 0, 0), Entity::PLACEHOLDER);
+        tick(&mut app, FLUID_TICK);
+        assert_eq!(
+            level(app.resource::<VoxelWorld>(), [3, 1, 3]),
+            Some(SOURCE_LEVEL - 1)
+        );
+    }
+}
@@ -0,0 +1,40 @@
+pub mod clipboard;
+pub mod edit;
+pub mod hotbar;
+pub mod target;
+
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::schedule::IntoSystemConfigs,
+};
+pub use clipboard::{
+    clipboard_actions, clipboard_handler, selection_handler, Clipboard, ClipboardAction,
+    CLIPBOARD_FILE,
+};
+pub use edit::{edit_handler, explosion_handler, history_handler, EXPLOSION_RADIUS};
+pub use hotbar::{hotbar_handler, Hotbar};
+pub use target::{highlight_handler, target_handler, TargetedVoxel, REACH};
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hotbar>()
+            .init_resource::<Clipboard>()
+            .init_resource::<TargetedVoxel>()
+            .add_systems(Update, hotbar_handler)
+            .add_systems(
+                Update,
+                (
+                    target_handler,
+                    highlight_handler,
+                    edit_handler,
+                    explosion_handler,
+                    history_handler,
+                    clipboard_handler,
+                    selection_handler,
+                )
+                    .chain(),
+            );
+    }
+}
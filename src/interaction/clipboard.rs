@@ -0,0 +1,275 @@
+use bevy::{
+    ecs::{
+        query::With,
+        system::{Query, Res, ResMut, Resource},
+    },
+    gizmos::gizmos::Gizmos,
+    input::{keyboard::KeyCode, Input},
+    log::warn,
+    math::Vec3,
+    render::color::Color,
+    transform::components::Transform,
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{
+    mesh::Axis,
+    player::cursor::is_grabbed,
+    schematic::{Schematic, VoxFile, SCHEMATIC_EXTENSION, VOX_EXTENSION},
+    util::WorldVoxelPos,
+    world::{EditHistory, VoxelWorld},
+};
+
+use super::TargetedVoxel;
+
+/// The name of the file the clipboard is saved to and loaded from, without its extension.
+pub const CLIPBOARD_FILE: &str = "clipboard";
+
+// Public struct `Clipboard` holding the corners of the selected box and the last copied schematic.
+#[derive(Resource, Clone, Debug)]
+pub struct Clipboard {
+    pub corners: [Option<WorldVoxelPos>; 2],
+    pub schematic: Option<Schematic>,
+    // Whether pasting leaves the world untouched where the schematic is empty.
+    pub skip_air: bool,
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self {
+            corners: [None; 2],
+            schematic: None,
+            skip_air: true,
+        }
+    }
+}
+
+// Public enum `ClipboardAction` listing what the clipboard keys do.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ClipboardAction {
+    // Select a corner of the box at the targeted voxel.
+    SelectFirst,
+    SelectSecond,
+    Copy,
+    Paste,
+    Rotate,
+    Mirror(Axis),
+    Save,
+    Load,
+    Export,
+    Import,
+}
+
+/// Public function `clipboard_actions` returns the clipboard actions triggered by the keys pressed this frame.
+/// Copying, pasting and the file actions are held on Alt, since Ctrl crouches and descends while flying
+/// and is usually held together with the movement keys.
+pub fn clipboard_actions(keys: &Input<KeyCode>) -> Vec<ClipboardAction> {
+    let alt = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    let mut actions = Vec::new();
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        actions.push(ClipboardAction::SelectFirst);
+    }
+    if keys.just_pressed(KeyCode::BracketRight) {
+        actions.push(ClipboardAction::SelectSecond);
+    }
+    if keys.just_pressed(KeyCode::R) {
+        actions.push(ClipboardAction::Rotate);
+    }
+    if keys.just_pressed(KeyCode::M) {
+        actions.push(ClipboardAction::Mirror(if shift {
+            Axis::Z
+        } else {
+            Axis::X
+        }));
+    }
+    if alt {
+        for (key, action) in [
+            (KeyCode::C, ClipboardAction::Copy),
+            (KeyCode::V, ClipboardAction::Paste),
+            (KeyCode::S, ClipboardAction::Save),
+            (KeyCode::O, ClipboardAction::Load),
+            (KeyCode::E, ClipboardAction::Export),
+            (KeyCode::I, ClipboardAction::Import),
+        ] {
+            if keys.just_pressed(key) {
+                actions.push(action);
+            }
+        }
+    }
+
+    actions
+}
+
+// This system handles the clipboard keys:
+// `[` and `]` select the corners of a box at the targeted voxel, Alt+C copies the box,
+// Alt+V pastes in front of the targeted face, R rotates and M mirrors the copy (Shift+M along Z),
+// Alt+S and Alt+O save and load it, and Alt+E and Alt+I export and import it as a MagicaVoxel file.
+pub fn clipboard_handler(
+    keys: Res<Input<KeyCode>>,
+    target: Res<TargetedVoxel>,
+    mut clipboard: ResMut<Clipboard>,
+    mut world: ResMut<VoxelWorld>,
+    mut history: ResMut<EditHistory>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    if !windows.get_single().map(is_grabbed).unwrap_or(false) {
+        return;
+    }
+    let path = format!("{CLIPBOARD_FILE}.{SCHEMATIC_EXTENSION}");
+    let vox_path = format!("{CLIPBOARD_FILE}.{VOX_EXTENSION}");
+
+    for action in clipboard_actions(&keys) {
+        match action {
+            ClipboardAction::SelectFirst => {
+                if let Some(hit) = target.0 {
+                    clipboard.corners[0] = Some(hit.position);
+                }
+            }
+            ClipboardAction::SelectSecond => {
+                if let Some(hit) = target.0 {
+                    clipboard.corners[1] = Some(hit.position);
+                }
+            }
+            ClipboardAction::Copy => {
+                if let [Some(a), Some(b)] = clipboard.corners {
+                    clipboard.schematic = Some(Schematic::copy(&world, a, b));
+                }
+            }
+            ClipboardAction::Paste => {
+                if let (Some(hit), Some(schematic)) = (target.0, clipboard.schematic.as_ref()) {
+                    let origin = hit.position.offset(hit.side.offset());
+                    history.push(schematic.paste(&mut world, origin, clipboard.skip_air));
+                }
+            }
+            ClipboardAction::Rotate => {
+                if let Some(schematic) = clipboard.schematic.as_mut() {
+                    *schematic = schematic.rotate();
+                }
+            }
+            ClipboardAction::Mirror(axis) => {
+                if let Some(schematic) = clipboard.schematic.as_mut() {
+                    *schematic = schematic.mirror(axis);
+                }
+            }
+            ClipboardAction::Save => {
+                if let Some(Err(error)) = clipboard
+                    .schematic
+                    .as_ref()
+                    .map(|schematic| schematic.save(&path))
+                {
+                    warn!("Could not save the clipboard to {path}: {error}");
+                }
+            }
+            ClipboardAction::Load => match Schematic::load(&path) {
+                Ok(schematic) => clipboard.schematic = Some(schematic),
+                Err(error) => warn!("Could not load the clipboard from {path}: {error}"),
+            },
+            ClipboardAction::Export => {
+                if let Some(schematic) = clipboard.schematic.as_ref() {
+                    let exported =
+                        VoxFile::from_schematics(&[(schematic.clone(), WorldVoxelPos::default())])
+                            .and_then(|file| file.save(&vox_path));
+                    if let Err(error) = exported {
+                        warn!("Could not export the clipboard to {vox_path}: {error}");
+                    }
+                }
+            }
+            ClipboardAction::Import => {
+                // Only the first model of the file is imported.
+                match VoxFile::load(&vox_path).and_then(|file| file.schematics()) {
+                    Ok(schematics) => {
+                        if let Some((schematic, _)) = schematics.into_iter().next() {
+                            clipboard.schematic = Some(schematic);
+                        }
+                    }
+                    Err(error) => warn!("Could not import the clipboard from {vox_path}: {error}"),
+                }
+            }
+        }
+    }
+}
+
+// This system draws a wireframe around the selected box.
+pub fn selection_handler(mut gizmos: Gizmos, clipboard: Res<Clipboard>, world: Res<VoxelWorld>) {
+    let corners = match clipboard.corners {
+        [Some(a), Some(b)] => [a, b],
+        [Some(a), None] | [None, Some(a)] => [a, a],
+        [None, None] => return,
+    };
+
+    let [a, b] = corners.map(|corner| world.config.voxel_center(corner));
+    // Span the whole voxels at both corners.
+    let min = a.min(b) - Vec3::splat(world.config.voxel_size / 2.0);
+    let max = a.max(b) + Vec3::splat(world.config.voxel_size / 2.0);
+    gizmos.cuboid(
+        Transform::from_translation((min + max) / 2.0).with_scale(max - min),
+        Color::YELLOW,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(keys: &[KeyCode]) -> Input<KeyCode> {
+        let mut input = Input::default();
+        for key in keys {
+            input.press(*key);
+        }
+        input
+    }
+
+    // Crouching while walking, or descending while flying, holds Ctrl together with other keys.
+    #[test]
+    fn crouching_triggers_no_clipboard_action() {
+        for key in [
+            KeyCode::W,
+            KeyCode::A,
+            KeyCode::S,
+            KeyCode::D,
+            KeyCode::C,
+            KeyCode::V,
+            KeyCode::O,
+            KeyCode::E,
+            KeyCode::I,
+        ] {
+            for control in [KeyCode::ControlLeft, KeyCode::ControlRight] {
+                assert_eq!(clipboard_actions(&pressed(&[control, key])), []);
+            }
+        }
+    }
+
+    #[test]
+    fn alt_triggers_the_clipboard_actions() {
+        let alt = |key| clipboard_actions(&pressed(&[KeyCode::AltLeft, key]));
+        assert_eq!(alt(KeyCode::C), [ClipboardAction::Copy]);
+        assert_eq!(alt(KeyCode::V), [ClipboardAction::Paste]);
+        assert_eq!(alt(KeyCode::S), [ClipboardAction::Save]);
+        assert_eq!(alt(KeyCode::O), [ClipboardAction::Load]);
+        assert_eq!(alt(KeyCode::E), [ClipboardAction::Export]);
+        assert_eq!(alt(KeyCode::I), [ClipboardAction::Import]);
+
+        // Without Alt the letters only move the player.
+        assert_eq!(clipboard_actions(&pressed(&[KeyCode::S])), []);
+    }
+
+    #[test]
+    fn selection_and_transforms_need_no_modifier() {
+        let keys = pressed(&[
+            KeyCode::BracketLeft,
+            KeyCode::R,
+            KeyCode::ShiftLeft,
+            KeyCode::M,
+        ]);
+        assert_eq!(
+            clipboard_actions(&keys),
+            [
+                ClipboardAction::SelectFirst,
+                ClipboardAction::Rotate,
+                ClipboardAction::Mirror(Axis::Z),
+            ]
+        );
+    }
+}
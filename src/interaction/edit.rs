@@ -0,0 +1,90 @@
+use bevy::{
+    ecs::{
+        query::With,
+        system::{Query, Res, ResMut},
+    },
+    input::{keyboard::KeyCode, mouse::MouseButton, Input},
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{
+    mesh::Voxel,
+    player::cursor::is_grabbed,
+    world::{EditHistory, VoxelWorld},
+};
+
+use super::{Hotbar, TargetedVoxel};
+
+/// The radius of the explosions set off at the targeted voxel, in voxels.
+pub const EXPLOSION_RADIUS: f32 = 3.0;
+
+// This system breaks the targeted voxel on left click and places the selected block against the targeted face on right click.
+// Edits go through `VoxelWorld::edit`, which takes care of remeshing the affected chunks, and are recorded for undoing.
+pub fn edit_handler(
+    mouse: Res<Input<MouseButton>>,
+    target: Res<TargetedVoxel>,
+    hotbar: Res<Hotbar>,
+    mut world: ResMut<VoxelWorld>,
+    mut history: ResMut<EditHistory>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    // Clicks that grab the cursor don't edit the world.
+    let grabbed = windows.get_single().map(is_grabbed).unwrap_or(false);
+    let Some(hit) = target.0.filter(|_| grabbed) else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        history.push(world.edit(hit.position, Voxel::Empty));
+    } else if mouse.just_pressed(MouseButton::Right) {
+        let Some(block) = hotbar.selected() else {
+            return;
+        };
+
+        // Place the block in front of the face the ray hit, unless something other than a liquid is already there.
+        let position = hit.position + hit.side.offset();
+        if let Some(Voxel::Empty | Voxel::Liquid(..)) = world.get(position) {
+            history.push(world.edit(position, block));
+        }
+    }
+}
+
+// This system blows up the terrain around the targeted voxel when X is pressed.
+pub fn explosion_handler(
+    keys: Res<Input<KeyCode>>,
+    target: Res<TargetedVoxel>,
+    mut world: ResMut<VoxelWorld>,
+    mut history: ResMut<EditHistory>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let grabbed = windows.get_single().map(is_grabbed).unwrap_or(false);
+    let Some(hit) = target.0.filter(|_| grabbed) else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::X) {
+        let position = hit.position;
+        let center = world.config.voxel_center(position);
+        let radius = EXPLOSION_RADIUS * world.config.voxel_size;
+        history.push(world.explode(center, radius));
+    }
+}
+
+// This system undoes the last edit on Ctrl+Z and redoes it on Ctrl+Y or Ctrl+Shift+Z.
+pub fn history_handler(
+    keys: Res<Input<KeyCode>>,
+    mut world: ResMut<VoxelWorld>,
+    mut history: ResMut<EditHistory>,
+) {
+    let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !control {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Y) || (shift && keys.just_pressed(KeyCode::Z)) {
+        history.redo(&mut world);
+    } else if keys.just_pressed(KeyCode::Z) {
+        history.undo(&mut world);
+    }
+}
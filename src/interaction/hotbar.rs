@@ -0,0 +1,83 @@
+use bevy::{
+    ecs::{
+        event::EventReader,
+        system::{Res, ResMut, Resource},
+        world::{FromWorld, World},
+    },
+    input::{keyboard::KeyCode, mouse::MouseWheel, Input},
+};
+
+use crate::{block::BlockRegistry, mesh::Voxel};
+
+// The keys selecting the slots of the hotbar, in slot order.
+const SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+// Public struct `Hotbar` holding the blocks the player can place and the selected one.
+#[derive(Resource, Clone, Debug)]
+pub struct Hotbar {
+    pub blocks: Vec<Voxel>,
+    pub selected: usize,
+}
+
+impl Hotbar {
+    // Public method `selected` returns the block placed on right click, if the hotbar isn't empty.
+    pub fn selected(&self) -> Option<Voxel> {
+        self.blocks.get(self.selected).copied()
+    }
+
+    // Public method `select` selects a slot, wrapping around the ends of the hotbar.
+    pub fn select(&mut self, slot: isize) {
+        if self.blocks.is_empty() {
+            return;
+        }
+        self.selected = slot.rem_euclid(self.blocks.len() as isize) as usize;
+    }
+}
+
+// The hotbar starts out holding every registered block.
+impl FromWorld for Hotbar {
+    fn from_world(world: &mut World) -> Self {
+        let blocks = world
+            .get_resource::<BlockRegistry>()
+            .map(|registry| registry.0.iter().map(|block| block.voxel).collect())
+            .unwrap_or_default();
+
+        Self {
+            blocks,
+            selected: 0,
+        }
+    }
+}
+
+// This system selects a hotbar slot with the number keys or the mouse wheel.
+pub fn hotbar_handler(
+    mut hotbar: ResMut<Hotbar>,
+    keys: Res<Input<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+) {
+    for (slot, key) in SLOT_KEYS.iter().enumerate() {
+        if keys.just_pressed(*key) && slot < hotbar.blocks.len() {
+            hotbar.selected = slot;
+        }
+    }
+
+    for event in wheel.read() {
+        // Scrolling down moves to the next slot.
+        let selected = hotbar.selected as isize;
+        if event.y < 0.0 {
+            hotbar.select(selected + 1);
+        } else if event.y > 0.0 {
+            hotbar.select(selected - 1);
+        }
+    }
+}
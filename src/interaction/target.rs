@@ -0,0 +1,58 @@
+use bevy::{
+    core_pipeline::core_3d::Camera3d,
+    ecs::{
+        query::With,
+        system::{Query, Res, ResMut, Resource},
+    },
+    gizmos::gizmos::Gizmos,
+    math::Vec3,
+    render::color::Color,
+    transform::components::{GlobalTransform, Transform},
+};
+
+use crate::{
+    mesh::Voxel,
+    world::{RaycastHit, VoxelWorld},
+};
+
+/// How far away voxels can be targeted, in voxels.
+pub const REACH: f32 = 8.0;
+
+// Public struct `TargetedVoxel` holding the voxel the camera is looking at, if any is within reach.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct TargetedVoxel(pub Option<RaycastHit>);
+
+// This system casts a ray from the camera to find the voxel it is looking at.
+pub fn target_handler(
+    // Query for the camera's global transform.
+    query: Query<&GlobalTransform, With<Camera3d>>,
+    world: Res<VoxelWorld>,
+    mut target: ResMut<TargetedVoxel>,
+) {
+    let Ok(transform) = query.get_single() else {
+        target.0 = None;
+        return;
+    };
+
+    target.0 = world.raycast(
+        transform.translation(),
+        transform.forward(),
+        REACH * world.config.voxel_size,
+        // Liquids are looked through, so blocks can be edited under water.
+        |voxel| !matches!(voxel, Voxel::Empty | Voxel::Liquid(..)),
+    );
+}
+
+// This system draws a wireframe around the targeted voxel.
+pub fn highlight_handler(mut gizmos: Gizmos, target: Res<TargetedVoxel>, world: Res<VoxelWorld>) {
+    let Some(hit) = target.0 else {
+        return;
+    };
+
+    // Grow the box slightly so it isn't hidden by the faces of the voxel.
+    let center = world.config.voxel_center(hit.position);
+    gizmos.cuboid(
+        Transform::from_translation(center).with_scale(Vec3::splat(world.config.voxel_size * 1.02)),
+        Color::BLACK,
+    );
+}
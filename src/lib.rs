@@ -0,0 +1,78 @@
+//! A voxel engine for Bevy: chunked voxel worlds with meshing, lighting, fluids, physics and a player.
+//!
+//! Add [`VoxelPlugins`] next to Bevy's `DefaultPlugins` to get a playable world,
+//! or pick the plugins you need and insert [`VoxelSettings`] yourself.
+//!
+//! Without the default `bevy` feature only the voxel data, terrain generation, lighting and meshing
+//! are built, so servers and tools can generate worlds and plain vertex and index buffers cheaply.
+
+pub mod block;
+pub mod cli;
+pub mod export;
+#[cfg(feature = "bevy")]
+pub mod falling;
+pub mod fluid;
+#[cfg(feature = "bevy")]
+pub mod interaction;
+pub mod light;
+pub mod map;
+pub mod mesh;
+pub mod physics;
+#[cfg(feature = "bevy")]
+pub mod player;
+pub mod schematic;
+#[cfg(feature = "bevy")]
+pub mod settings;
+pub mod util;
+pub mod world;
+
+#[cfg(feature = "bevy")]
+use bevy::app::{PluginGroup, PluginGroupBuilder};
+
+pub use block::{BlockDefinition, BlockRegistry};
+pub use export::ExportFormat;
+pub use mesh::{
+    ChunkMesh, GenerationSettings, MeshAlgorithm, MeshBuffers, Voxel, CHUNK_SIZE, VOXEL_SIZE,
+};
+pub use schematic::Schematic;
+pub use util::{ChunkPos, Color, LocalPos, WorldVoxelPos};
+pub use world::{VoxelWorld, WorldConfig};
+
+#[cfg(feature = "bevy")]
+pub use {
+    falling::FallingPlugin,
+    fluid::FluidPlugin,
+    interaction::InteractionPlugin,
+    light::LightPlugin,
+    map::MapPlugin,
+    mesh::MeshPlugin,
+    physics::PhysicsPlugin,
+    player::PlayerPlugin,
+    settings::{SettingsPlugin, VoxelSettings},
+    world::WorldPlugin,
+};
+
+// Public struct `VoxelPlugins` adding every plugin of the engine, configured with `settings`.
+#[cfg(feature = "bevy")]
+#[derive(Default)]
+pub struct VoxelPlugins {
+    pub settings: VoxelSettings,
+}
+
+#[cfg(feature = "bevy")]
+impl PluginGroup for VoxelPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        // The settings go first so the plugins initializing them don't replace them with the defaults.
+        PluginGroupBuilder::start::<Self>()
+            .add(SettingsPlugin(self.settings))
+            .add(MeshPlugin)
+            .add(WorldPlugin)
+            .add(InteractionPlugin)
+            .add(LightPlugin)
+            .add(FluidPlugin)
+            .add(FallingPlugin)
+            .add(PhysicsPlugin)
+            .add(PlayerPlugin)
+            .add(MapPlugin)
+    }
+}
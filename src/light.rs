@@ -0,0 +1,35 @@
+pub mod chunk;
+pub mod level;
+pub mod propagation;
+#[cfg(feature = "bevy")]
+pub mod sun;
+
+#[cfg(feature = "bevy")]
+use bevy::{
+    app::{App, Plugin, Startup, Update},
+    ecs::schedule::IntoSystemConfigs,
+};
+pub use level::{transmits, Channel, Emission, Light, LIGHT_FALLOFF, MAX_LIGHT};
+#[cfg(feature = "bevy")]
+pub use propagation::light_handler;
+#[cfg(feature = "bevy")]
+pub use sun::setup_sun;
+
+#[cfg(feature = "bevy")]
+use crate::{interaction::clipboard_handler, world::remesh_handler};
+
+#[cfg(feature = "bevy")]
+pub struct LightPlugin;
+
+#[cfg(feature = "bevy")]
+impl Plugin for LightPlugin {
+    fn build(&self, app: &mut App) {
+        // Edits are relit before the chunks they touched are remeshed.
+        app.add_systems(Startup, setup_sun).add_systems(
+            Update,
+            light_handler
+                .after(clipboard_handler)
+                .before(remesh_handler),
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::VecDeque;
+
+use crate::mesh::{ChunkMesh, Side};
+
+use super::{transmits, Channel, Emission, Light, MAX_LIGHT};
+
+impl ChunkMesh {
+    /// Public method `compute_light` lights the chunk on its own, assuming open sky above it and
+    /// darkness on every other side. Skylight falls down every column until it hits an opaque voxel,
+    /// then both sky and block light spread through the chunk with a breadth-first search.
+    /// The light of the neighboring chunks is merged in when the chunk is added to the world.
+    pub fn compute_light(&mut self, emission: &Emission) {
+        let (top, size) = (self.padded() - 1, self.config.chunk_size);
+        self.light = vec![Light::default(); self.size()];
+
+        let mut sky = VecDeque::new();
+        for z in 0..self.padded() {
+            for x in 0..self.padded() {
+                let mut light = Light::default();
+                light.set(Channel::Sky, MAX_LIGHT);
+                self.set_light(x, top, z, light);
+            }
+        }
+        for z in 1..=size {
+            for x in 1..=size {
+                for y in (1..=size).rev() {
+                    if !transmits(self.get(x, y, z)) {
+                        break;
+                    }
+                    let mut light = self.light_at(x, y, z);
+                    light.set(Channel::Sky, MAX_LIGHT);
+                    self.set_light(x, y, z, light);
+                    sky.push_back([x, y, z]);
+                }
+            }
+        }
+        self.spread_light(Channel::Sky, sky);
+
+        // Every color of block light spreads on its own, mixing where they overlap.
+        for channel in [Channel::Red, Channel::Green, Channel::Blue] {
+            let mut block = VecDeque::new();
+            for z in 1..=size {
+                for y in 1..=size {
+                    for x in 1..=size {
+                        let Some(level) = emission
+                            .get(&self.get(x, y, z))
+                            .and_then(|emission| channel.emitted(*emission))
+                        else {
+                            continue;
+                        };
+                        let mut light = self.light_at(x, y, z);
+                        light.set(channel, level);
+                        self.set_light(x, y, z, light);
+                        block.push_back([x, y, z]);
+                    }
+                }
+            }
+            self.spread_light(channel, block);
+        }
+    }
+
+    // Private method `spread_light` spreads the light of the queued voxels through the inside of the chunk.
+    fn spread_light(&mut self, channel: Channel, mut queue: VecDeque<[usize; 3]>) {
+        let size = self.config.chunk_size;
+
+        while let Some([x, y, z]) = queue.pop_front() {
+            let level = self.light_at(x, y, z).get(channel);
+
+            for index in 0..6 {
+                let side = Side::from(index);
+                let [dx, dy, dz] = side.offset();
+                let neighbor = [
+                    (x as i32 + dx) as usize,
+                    (y as i32 + dy) as usize,
+                    (z as i32 + dz) as usize,
+                ];
+                if neighbor.iter().any(|n| *n < 1 || *n > size) {
+                    continue;
+                }
+
+                let [nx, ny, nz] = neighbor;
+                let spread = channel.spread(level, dy < 0);
+                let mut light = self.light_at(nx, ny, nz);
+                if light.get(channel) >= spread || !transmits(self.get(nx, ny, nz)) {
+                    continue;
+                }
+
+                light.set(channel, spread);
+                self.set_light(nx, ny, nz, light);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::STONE,
+        mesh::Voxel,
+        util::{ChunkPos, Color},
+        world::WorldConfig,
+    };
+
+    // Function `chunk` returns an empty chunk with stone at the given padded positions.
+    fn chunk(stone: &[[usize; 3]]) -> ChunkMesh {
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), WorldConfig::new(8, 1.0));
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        for [x, y, z] in stone {
+            chunk.set(*x, *y, *z, STONE);
+        }
+        chunk
+    }
+
+    fn sky(chunk: &ChunkMesh, [x, y, z]: [usize; 3]) -> u8 {
+        chunk.light_at(x, y, z).get(Channel::Sky)
+    }
+
+    #[test]
+    fn skylight_falls_down_columns() {
+        let mut chunk = chunk(&[[4, 6, 4]]);
+        chunk.compute_light(&Emission::default());
+
+        // Open columns are fully lit down to the bottom.
+        for y in 1..=8 {
+            assert_eq!(sky(&chunk, [2, y, 2]), MAX_LIGHT);
+        }
+        // Above the stone the column is fully lit, below it the light only comes in from the side.
+        assert_eq!(sky(&chunk, [4, 7, 4]), MAX_LIGHT);
+        assert_eq!(sky(&chunk, [4, 6, 4]), 0);
+        for y in 1..6 {
+            assert_eq!(sky(&chunk, [4, y, 4]), MAX_LIGHT - 1, "{y}");
+        }
+    }
+
+    #[test]
+    fn block_light_spreads_per_channel() {
+        let lamp = Voxel::Opaque(Color::new(255, 255, 0));
+        // A closed box of stone, so no skylight gets in.
+        let mut walls = Vec::new();
+        for a in 1..=8 {
+            for b in 1..=8 {
+                walls.extend([
+                    [a, b, 1],
+                    [a, b, 8],
+                    [a, 1, b],
+                    [a, 8, b],
+                    [1, a, b],
+                    [8, a, b],
+                ]);
+            }
+        }
+        let mut chunk = chunk(&walls);
+        chunk.set(4, 4, 4, lamp);
+        chunk.compute_light(&Emission::from([(lamp, [12, 6, 0])]));
+
+        let light = chunk.light_at(4, 4, 6);
+        assert_eq!(light.get(Channel::Sky), 0);
+        assert_eq!(light.get(Channel::Red), 10);
+        assert_eq!(light.get(Channel::Green), 4);
+        assert_eq!(light.get(Channel::Blue), 0);
+        // Light doesn't get into opaque voxels.
+        assert_eq!(chunk.light_at(4, 4, 8), Light::default());
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use crate::{mesh::Voxel, util::OPAQUE};
+
+/// The highest light level, the level of direct sunlight.
+pub const MAX_LIGHT: u8 = 15;
+
+/// How much darker every light level is than the one above it.
+pub const LIGHT_FALLOFF: f32 = 0.8;
+
+/// The red, green and blue light levels emitted by every emissive block.
+pub type Emission = HashMap<Voxel, [u8; 3]>;
+
+// Public enum `Channel` naming the kinds of light, which spread independently.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Channel {
+    // Light from the sky, which falls straight down without getting darker.
+    Sky,
+    // The red, green and blue components of the light from emissive blocks.
+    Red,
+    Green,
+    Blue,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 4] = [Channel::Sky, Channel::Red, Channel::Green, Channel::Blue];
+
+    // Public method `spread` returns the level light of this channel has after moving one voxel,
+    // `downwards` being whether it moves down.
+    pub fn spread(&self, level: u8, downwards: bool) -> u8 {
+        match self {
+            Channel::Sky if downwards && level == MAX_LIGHT => MAX_LIGHT,
+            _ => level.saturating_sub(1),
+        }
+    }
+
+    // Public method `emitted` returns the level of this channel in the light emitted by a block, if any.
+    pub fn emitted(&self, emission: [u8; 3]) -> Option<u8> {
+        match self {
+            Channel::Sky => None,
+            Channel::Red => Some(emission[0]),
+            Channel::Green => Some(emission[1]),
+            Channel::Blue => Some(emission[2]),
+        }
+    }
+
+    // Private method `shift` returns the position of the four bits of this channel.
+    fn shift(&self) -> u16 {
+        match self {
+            Channel::Sky => 12,
+            Channel::Red => 8,
+            Channel::Green => 4,
+            Channel::Blue => 0,
+        }
+    }
+}
+
+// Public struct `Light` packing the sky light and the red, green and blue block light into four bits each.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Hash)]
+pub struct Light(pub u16);
+
+impl Light {
+    pub fn get(&self, channel: Channel) -> u8 {
+        ((self.0 >> channel.shift()) & 0x0f) as u8
+    }
+
+    pub fn set(&mut self, channel: Channel, level: u8) {
+        let shift = channel.shift();
+        let level = level.min(MAX_LIGHT) as u16;
+        self.0 = (self.0 & !(0x0f << shift)) | (level << shift);
+    }
+
+    /// Public method `color` returns how much a face lit by this light is darkened in every color
+    /// component, between 0 and 1. Sky light is white, block light mixes in its own color, and every
+    /// component is as bright as the brightest light reaching it.
+    pub fn color(&self) -> [f32; 3] {
+        let brightness = |level: u8| LIGHT_FALLOFF.powi((MAX_LIGHT - level) as i32);
+        let sky = self.get(Channel::Sky);
+
+        [Channel::Red, Channel::Green, Channel::Blue]
+            .map(|channel| brightness(sky.max(self.get(channel))))
+    }
+
+    // Public method `max` combines two lights, keeping the brightest level of every channel.
+    pub fn max(self, other: Self) -> Self {
+        let mut light = self;
+        for channel in Channel::ALL {
+            light.set(channel, self.get(channel).max(other.get(channel)));
+        }
+        light
+    }
+}
+
+// Function `transmits` checks if light passes through a voxel.
+pub fn transmits(voxel: Voxel) -> bool {
+    voxel.visibility() != OPAQUE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_dont_bleed_into_each_other() {
+        for channel in Channel::ALL {
+            let mut light = Light(0);
+            light.set(channel, MAX_LIGHT);
+            for other in Channel::ALL {
+                let expected = if other == channel { MAX_LIGHT } else { 0 };
+                assert_eq!(light.get(other), expected, "{channel:?} {other:?}");
+            }
+
+            // Clearing a channel leaves the others full.
+            let mut light = Light(u16::MAX);
+            light.set(channel, 0);
+            for other in Channel::ALL {
+                let expected = if other == channel { 0 } else { MAX_LIGHT };
+                assert_eq!(light.get(other), expected, "{channel:?} {other:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn levels_are_clamped() {
+        let mut light = Light::default();
+        light.set(Channel::Red, 200);
+        assert_eq!(light.get(Channel::Red), MAX_LIGHT);
+        assert_eq!(light.get(Channel::Sky), 0);
+        assert_eq!(light.get(Channel::Green), 0);
+    }
+
+    #[test]
+    fn sources_mix_per_channel() {
+        let mut red = Light::default();
+        red.set(Channel::Red, 12);
+        red.set(Channel::Green, 3);
+        let mut blue = Light::default();
+        blue.set(Channel::Blue, 9);
+        blue.set(Channel::Green, 5);
+
+        let mixed = red.max(blue);
+        assert_eq!(mixed.get(Channel::Red), 12);
+        assert_eq!(mixed.get(Channel::Green), 5);
+        assert_eq!(mixed.get(Channel::Blue), 9);
+        assert_eq!(mixed.get(Channel::Sky), 0);
+
+        // Every level below the maximum is `LIGHT_FALLOFF` times darker.
+        let [r, g, b] = mixed.color();
+        assert!((r - LIGHT_FALLOFF.powi(3)).abs() < 1e-6);
+        assert!((g - LIGHT_FALLOFF.powi(10)).abs() < 1e-6);
+        assert!((b - LIGHT_FALLOFF.powi(6)).abs() < 1e-6);
+
+        // Skylight is white and brightens every component.
+        let mut lit = mixed;
+        lit.set(Channel::Sky, 10);
+        let [r, g, b] = lit.color();
+        assert!((r - LIGHT_FALLOFF.powi(3)).abs() < 1e-6);
+        assert_eq!(g, b);
+    }
+
+    #[test]
+    fn only_full_skylight_falls_without_dimming() {
+        assert_eq!(Channel::Sky.spread(MAX_LIGHT, true), MAX_LIGHT);
+        assert_eq!(Channel::Sky.spread(MAX_LIGHT, false), MAX_LIGHT - 1);
+        assert_eq!(Channel::Sky.spread(10, true), 9);
+        assert_eq!(Channel::Red.spread(MAX_LIGHT, true), MAX_LIGHT - 1);
+        assert_eq!(Channel::Blue.spread(0, false), 0);
+    }
+}
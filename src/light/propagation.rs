@@ -0,0 +1,469 @@
+use std::collections::VecDeque;
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::system::{Res, ResMut};
+
+#[cfg(feature = "bevy")]
+use crate::block::BlockRegistry;
+use crate::{
+    mesh::{ChunkMesh, Side, Voxel},
+    util::{ChunkPos, WorldVoxelPos, FACE_OFFSETS},
+    world::VoxelWorld,
+};
+
+use super::{transmits, Channel, Emission, Light, MAX_LIGHT};
+
+impl VoxelWorld {
+    /// Public method `light` returns the light at a world voxel position, or `None` if its chunk isn't generated.
+    /// Voxels right above a generated chunk whose upper neighbor isn't generated are lit by the open sky
+    /// assumed in its padding.
+    pub fn light(&self, position: WorldVoxelPos) -> Option<Light> {
+        let (chunk, local) = self.config.split(position);
+        let [x, y, z] = local.padded();
+        if let Some(chunk) = self.chunks.get(&chunk) {
+            return Some(chunk.light_at(x, y, z));
+        }
+
+        if local.y != 0 {
+            return None;
+        }
+        self.chunks
+            .get(&chunk.offset([0, -1, 0]))
+            .map(|below| below.light_at(x, below.padded() - 1, z))
+    }
+
+    // Private method `set_light` changes the light at a world voxel position in every chunk holding it,
+    // marking chunks dirty when a face they draw is lit by it.
+    fn set_light(&mut self, position: WorldVoxelPos, light: Light) {
+        if self.get(position).is_none() {
+            return;
+        }
+
+        for (chunk_position, [x, y, z]) in self.config.containing(position) {
+            let Some(chunk) = self.chunks.get_mut(&chunk_position) else {
+                continue;
+            };
+            if chunk.light.is_empty() || chunk.light_at(x, y, z) == light {
+                continue;
+            }
+
+            chunk.set_light(x, y, z, light);
+            if lights_faces(chunk, [x, y, z]) {
+                self.dirty.insert(chunk_position);
+            }
+        }
+    }
+
+    // Public method `spread_light` spreads the light of the queued voxels through the world, across chunks.
+    pub fn spread_light(&mut self, channel: Channel, mut queue: VecDeque<WorldVoxelPos>) {
+        while let Some(position) = queue.pop_front() {
+            let Some(light) = self.light(position) else {
+                continue;
+            };
+            let level = light.get(channel);
+
+            for offset in FACE_OFFSETS {
+                let neighbor = position + offset;
+                let spread = channel.spread(level, offset[1] < 0);
+                if spread == 0 || !self.get(neighbor).is_some_and(transmits) {
+                    continue;
+                }
+
+                let Some(mut light) = self.light(neighbor) else {
+                    continue;
+                };
+                if light.get(channel) >= spread {
+                    continue;
+                }
+
+                light.set(channel, spread);
+                self.set_light(neighbor, light);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    /// Public method `remove_light` darkens every voxel lit by the queued voxels, which must already be dark,
+    /// given the level of light they had. Returns the voxels around the darkened area that are lit by
+    /// something else, which have to be spread again with `spread_light` to fill it back in.
+    pub fn remove_light(
+        &mut self,
+        channel: Channel,
+        mut queue: VecDeque<(WorldVoxelPos, u8)>,
+        emission: &Emission,
+    ) -> VecDeque<WorldVoxelPos> {
+        let mut relight = VecDeque::new();
+
+        while let Some((position, level)) = queue.pop_front() {
+            for offset in FACE_OFFSETS {
+                let neighbor = position + offset;
+                let Some(mut light) = self.light(neighbor) else {
+                    continue;
+                };
+                let neighbor_level = light.get(channel);
+                if neighbor_level == 0 {
+                    continue;
+                }
+
+                // Voxels that could have been lit by the removed light go dark, any other light is a source.
+                let voxel = self.get(neighbor);
+                if voxel.is_none() || channel.spread(level, offset[1] < 0) < neighbor_level {
+                    relight.push_back(neighbor);
+                    continue;
+                }
+
+                light.set(channel, 0);
+                self.set_light(neighbor, light);
+                queue.push_back((neighbor, neighbor_level));
+
+                // Emissive voxels keep their own light.
+                if let Some(level) = emission_of(channel, voxel, emission) {
+                    light.set(channel, level);
+                    self.set_light(neighbor, light);
+                    relight.push_back(neighbor);
+                }
+            }
+        }
+
+        relight
+    }
+
+    /// Public method `update_light` relights the voxels that changed since the last update.
+    /// The light at every changed voxel is removed and spread again from the voxel and its surroundings,
+    /// which handles both placing and removing opaque and emissive voxels.
+    pub fn update_light(&mut self, emission: &Emission) {
+        if self.relight.is_empty() {
+            return;
+        }
+        let positions = std::mem::take(&mut self.relight);
+
+        for channel in Channel::ALL {
+            let mut removal = VecDeque::new();
+            for position in positions.iter() {
+                let Some(mut light) = self.light(*position) else {
+                    continue;
+                };
+                removal.push_back((*position, light.get(channel)));
+                light.set(channel, 0);
+                self.set_light(*position, light);
+            }
+
+            let mut queue = self.remove_light(channel, removal, emission);
+            for position in positions.iter() {
+                let voxel = self.get(*position);
+                if let (Some(level), Some(mut light)) =
+                    (emission_of(channel, voxel, emission), self.light(*position))
+                {
+                    light.set(channel, level);
+                    self.set_light(*position, light);
+                }
+
+                queue.push_back(*position);
+                queue.extend(position.neighbors());
+            }
+            self.spread_light(channel, queue);
+        }
+    }
+
+    /// Public method `merge_light` merges the light of a chunk that was just added to the world, and lit on
+    /// its own, with the light of its neighbors. Their light is copied into each other's padding,
+    /// skylight assumed to come from above where the chunk above is dark is removed, and light is spread
+    /// across the borders in both directions.
+    pub fn merge_light(&mut self, position: ChunkPos, emission: &Emission) {
+        let Some(chunk) = self.chunks.get(&position) else {
+            return;
+        };
+        if chunk.light.is_empty() {
+            return;
+        }
+
+        let config = self.config;
+        let size = config.chunk_size;
+        let origin = config.chunk_origin(position);
+        let world = |[x, y, z]: [usize; 3]| -> WorldVoxelPos {
+            origin + [x as i32 - 1, y as i32 - 1, z as i32 - 1]
+        };
+
+        // Copy the light of the generated neighbors into the padding of the chunk.
+        let mut padding = Vec::new();
+        for i in 0..chunk.size() {
+            let (x, y, z) = chunk.delinearize(i);
+            if [x, y, z].iter().all(|p| *p >= 1 && *p <= size) {
+                continue;
+            }
+
+            let (owner, local) = config.split(world([x, y, z]));
+            if let Some(neighbor) = self.chunks.get(&owner) {
+                let [lx, ly, lz] = local.padded();
+                padding.push(([x, y, z], neighbor.light_at(lx, ly, lz)));
+            }
+        }
+        let chunk = self.chunks.get_mut(&position).unwrap();
+        for ([x, y, z], light) in padding {
+            chunk.set_light(x, y, z, light);
+        }
+
+        // Copy the light of the border of the chunk into the padding of its neighbors.
+        let chunk = &self.chunks[&position];
+        let border: Vec<[usize; 3]> = (0..chunk.size())
+            .map(|i| chunk.delinearize(i))
+            .map(|(x, y, z)| [x, y, z])
+            .filter(|p| p.iter().all(|p| *p >= 1 && *p <= size))
+            .filter(|p| p.iter().any(|p| *p == 1 || *p == size))
+            .collect();
+        for voxel in border.iter() {
+            let light = self.chunks[&position].light_at(voxel[0], voxel[1], voxel[2]);
+            self.set_light(world(*voxel), light);
+        }
+
+        // Remove skylight that was assumed to come from above, where the chunk above is dark.
+        if self.chunks.contains_key(&position.offset([0, 1, 0])) {
+            let mut removal = VecDeque::new();
+            for z in 1..=size {
+                for x in 1..=size {
+                    let chunk = &self.chunks[&position];
+                    let mut light = chunk.light_at(x, size, z);
+                    if light.get(Channel::Sky) == MAX_LIGHT
+                        && chunk.light_at(x, size + 1, z).get(Channel::Sky) < MAX_LIGHT
+                    {
+                        light.set(Channel::Sky, 0);
+                        self.set_light(world([x, size, z]), light);
+                        removal.push_back((world([x, size, z]), MAX_LIGHT));
+                    }
+                }
+            }
+            let relight = self.remove_light(Channel::Sky, removal, emission);
+            self.spread_light(Channel::Sky, relight);
+        }
+
+        // The chunk below assumed open sky above it, remove it where this chunk is dark.
+        if self.chunks.contains_key(&position.offset([0, -1, 0])) {
+            let mut removal = VecDeque::new();
+            for z in 1..=size {
+                for x in 1..=size {
+                    let chunk = &self.chunks[&position];
+                    let mut light = chunk.light_at(x, 0, z);
+                    if light.get(Channel::Sky) == MAX_LIGHT
+                        && chunk.light_at(x, 1, z).get(Channel::Sky) < MAX_LIGHT
+                    {
+                        light.set(Channel::Sky, 0);
+                        self.set_light(world([x, 0, z]), light);
+                        removal.push_back((world([x, 0, z]), MAX_LIGHT));
+                    }
+                }
+            }
+            let relight = self.remove_light(Channel::Sky, removal, emission);
+            self.spread_light(Channel::Sky, relight);
+        }
+
+        // Spread light across the border wherever one side is brighter than the other allows.
+        for channel in Channel::ALL {
+            let mut queue = VecDeque::new();
+            for voxel in border.iter() {
+                for index in 0..6 {
+                    let side = Side::from(index);
+                    let [dx, dy, dz] = side.offset();
+                    let outside = [
+                        (voxel[0] as i32 + dx) as usize,
+                        (voxel[1] as i32 + dy) as usize,
+                        (voxel[2] as i32 + dz) as usize,
+                    ];
+                    if outside.iter().all(|p| *p >= 1 && *p <= size) {
+                        continue;
+                    }
+
+                    let chunk = &self.chunks[&position];
+                    let inside_level = chunk.light_at(voxel[0], voxel[1], voxel[2]).get(channel);
+                    let outside_level = chunk
+                        .light_at(outside[0], outside[1], outside[2])
+                        .get(channel);
+
+                    if transmits(chunk.get(voxel[0], voxel[1], voxel[2]))
+                        && channel.spread(outside_level, dy > 0) > inside_level
+                    {
+                        queue.push_back(world(outside));
+                    }
+                    if transmits(chunk.get(outside[0], outside[1], outside[2]))
+                        && channel.spread(inside_level, dy < 0) > outside_level
+                    {
+                        queue.push_back(world(*voxel));
+                    }
+                }
+            }
+            self.spread_light(channel, queue);
+        }
+    }
+}
+
+// Function `emission_of` returns the light an optional voxel emits on a channel, if it emits any.
+fn emission_of(channel: Channel, voxel: Option<Voxel>, emission: &Emission) -> Option<u8> {
+    voxel
+        .and_then(|voxel| emission.get(&voxel))
+        .and_then(|emission| channel.emitted(*emission))
+        .filter(|level| *level > 0)
+}
+
+// Function `lights_faces` checks if the light at a padded position lights a face drawn by the chunk,
+// which is the case when a voxel inside the chunk next to it isn't empty.
+fn lights_faces(chunk: &ChunkMesh, [x, y, z]: [usize; 3]) -> bool {
+    let size = chunk.config.chunk_size as i32;
+    (0..6).any(|index| {
+        let [dx, dy, dz] = Side::from(index).offset();
+        let neighbor = [x as i32 + dx, y as i32 + dy, z as i32 + dz];
+        neighbor.iter().all(|p| *p >= 1 && *p <= size)
+            && chunk.get(
+                neighbor[0] as usize,
+                neighbor[1] as usize,
+                neighbor[2] as usize,
+            ) != Voxel::Empty
+    })
+}
+
+// This system relights the voxels that were changed by edits, before the affected chunks are remeshed.
+#[cfg(feature = "bevy")]
+pub fn light_handler(mut world: ResMut<VoxelWorld>, registry: Res<BlockRegistry>) {
+    world.update_light(&registry.emission());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::STONE, util::Color, world::WorldConfig};
+
+    const LAMP: Voxel = Voxel::Opaque(Color::new(255, 200, 100));
+
+    fn emission() -> Emission {
+        Emission::from([(LAMP, [14, 0, 0])])
+    }
+
+    // Function `world` generates and lights the chunks at `positions` in order, filling every voxel with `fill`.
+    fn world(positions: &[ChunkPos], fill: impl Fn(WorldVoxelPos) -> Voxel) -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        for position in positions {
+            let mut chunk = ChunkMesh::new(*position, config);
+            let origin = config.chunk_origin(*position);
+            chunk.voxels = (0..chunk.size())
+                .map(|i| {
+                    let (x, y, z) = chunk.delinearize(i);
+                    fill(origin + [x as i32 - 1, y as i32 - 1, z as i32 - 1])
+                })
+                .collect();
+            chunk.compute_light(&emission());
+            world.insert(chunk, &emission());
+        }
+        world
+    }
+
+    fn level(world: &VoxelWorld, channel: Channel, position: [i32; 3]) -> u8 {
+        world.light(position.into()).unwrap().get(channel)
+    }
+
+    // Function `assert_same_light` checks that two worlds hold the same light in every chunk.
+    fn assert_same_light(world: &VoxelWorld, expected: &VoxelWorld) {
+        for (position, chunk) in expected.chunks.iter() {
+            let light = &world.chunks[position].light;
+            for (i, expected) in chunk.light.iter().enumerate() {
+                let (x, y, z) = chunk.delinearize(i);
+                let padding = [x, y, z]
+                    .iter()
+                    .any(|p| *p == 0 || *p == chunk.padded() - 1);
+                if !padding {
+                    assert_eq!(light[i], *expected, "{position:?} {x} {y} {z}");
+                }
+            }
+        }
+    }
+
+    // A tunnel along X through stone, at y = 3 and z = 3.
+    fn tunnel(lamp: bool) -> impl Fn(WorldVoxelPos) -> Voxel {
+        move |position| match [position.x, position.y, position.z] {
+            [2, 3, 3] if lamp => LAMP,
+            [_, 3, 3] => Voxel::Empty,
+            _ => STONE,
+        }
+    }
+
+    #[test]
+    fn skylight_is_blocked_by_the_chunk_above() {
+        let chunks = [ChunkPos::new(0, 0, 0), ChunkPos::new(0, 1, 0)];
+        // A stone layer in the upper chunk, open sky above it and empty space below.
+        let fill = |position: WorldVoxelPos| match position.y {
+            10 => STONE,
+            _ => Voxel::Empty,
+        };
+
+        for order in [chunks, [chunks[1], chunks[0]]] {
+            let world = world(&order, fill);
+            assert_eq!(level(&world, Channel::Sky, [3, 11, 3]), MAX_LIGHT);
+            for y in 0..10 {
+                assert_eq!(level(&world, Channel::Sky, [3, y, 3]), 0, "{y}");
+            }
+        }
+    }
+
+    #[test]
+    fn block_light_spreads_across_chunk_seams() {
+        let chunks = [ChunkPos::new(0, 0, 0), ChunkPos::new(1, 0, 0)];
+        for order in [chunks, [chunks[1], chunks[0]]] {
+            let world = world(&order, tunnel(true));
+            for x in 0..16i32 {
+                let expected = 14u8.saturating_sub(x.abs_diff(2) as u8);
+                assert_eq!(level(&world, Channel::Red, [x, 3, 3]), expected, "{x}");
+                assert_eq!(level(&world, Channel::Green, [x, 3, 3]), 0);
+                assert_eq!(level(&world, Channel::Sky, [x, 3, 3]), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn removing_an_emitter_darkens_its_light() {
+        let chunks = [ChunkPos::new(0, 0, 0), ChunkPos::new(1, 0, 0)];
+        let mut world = world(&chunks, tunnel(true));
+        world.set(WorldVoxelPos::new(2, 3, 3), Voxel::Empty);
+        world.update_light(&emission());
+
+        for x in 0..16 {
+            assert_eq!(level(&world, Channel::Red, [x, 3, 3]), 0, "{x}");
+        }
+        assert_same_light(&world, &self::world(&chunks, tunnel(false)));
+
+        // Placing it back lights the tunnel again.
+        world.set(WorldVoxelPos::new(2, 3, 3), LAMP);
+        world.update_light(&emission());
+        assert_same_light(&world, &self::world(&chunks, tunnel(true)));
+    }
+
+    #[test]
+    fn light_fills_back_in_after_removing_an_occluder() {
+        let chunks = [ChunkPos::new(0, 0, 0)];
+        let roof = |position: WorldVoxelPos| match [position.x, position.y, position.z] {
+            [2..=4, 6, 2..=4] => STONE,
+            _ => Voxel::Empty,
+        };
+        let mut world = world(&chunks, |_| Voxel::Empty);
+
+        for x in 2..=4 {
+            for z in 2..=4 {
+                world.set(WorldVoxelPos::new(x, 6, z), STONE);
+            }
+        }
+        world.update_light(&emission());
+        // Below the middle of the roof, skylight only comes in from two voxels away.
+        for y in 0..6 {
+            assert_eq!(level(&world, Channel::Sky, [3, y, 3]), MAX_LIGHT - 2, "{y}");
+        }
+        assert_same_light(&world, &self::world(&chunks, roof));
+
+        for x in 2..=4 {
+            for z in 2..=4 {
+                world.set(WorldVoxelPos::new(x, 6, z), Voxel::Empty);
+            }
+        }
+        world.update_light(&emission());
+        for y in 0..8 {
+            assert_eq!(level(&world, Channel::Sky, [3, y, 3]), MAX_LIGHT, "{y}");
+        }
+        assert_same_light(&world, &self::world(&chunks, |_| Voxel::Empty));
+    }
+}
@@ -0,0 +1,29 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    ecs::system::Commands,
+    math::{Quat, Vec3},
+    pbr::{DirectionalLight, DirectionalLightBundle},
+    render::color::Color,
+    transform::components::Transform,
+    utils::default,
+};
+
+// This system spawns the directional 'sun' light lighting the voxel terrain.
+pub fn setup_sun(mut commands: Commands) {
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            // caves are darkened by the voxel light instead of shadows
+            shadows_enabled: false,
+            illuminance: 20000.0,
+            color: Color::rgb(253.0 / 255.0, 251.0 / 255.0, 211.0 / 255.0),
+            ..default()
+        },
+        transform: Transform {
+            translation: Vec3::new(0.0, 2.0, 0.0),
+            rotation: Quat::from_rotation_x(-PI / 4.),
+            ..default()
+        },
+        ..default()
+    });
+}
@@ -0,0 +1,21 @@
+#[cfg(feature = "bevy")]
+pub mod minimap;
+pub mod png;
+pub mod render;
+
+#[cfg(feature = "bevy")]
+use bevy::app::{App, Plugin, Startup, Update};
+#[cfg(feature = "bevy")]
+pub use minimap::{minimap_handler, setup_minimap, Minimap, MINIMAP_INTERVAL, MINIMAP_SIZE};
+pub use render::{map_size, MapImage, HILLSHADE, MAX_MAP_PIXELS};
+
+#[cfg(feature = "bevy")]
+pub struct MapPlugin;
+
+#[cfg(feature = "bevy")]
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_minimap)
+            .add_systems(Update, minimap_handler);
+    }
+}
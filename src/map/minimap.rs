@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{Assets, Handle},
+    core_pipeline::core_3d::Camera3d,
+    ecs::{
+        event::EventReader,
+        query::With,
+        system::{Commands, Query, Res, ResMut, Resource},
+    },
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::Image,
+    },
+    time::{Time, Timer, TimerMode},
+    transform::components::GlobalTransform,
+    ui::{node_bundles::ImageBundle, PositionType, Style, UiImage, Val},
+};
+
+use crate::{mesh::ChunkLoadEvent, world::VoxelWorld};
+
+/// The number of voxels shown along each side of the minimap.
+pub const MINIMAP_SIZE: usize = 128;
+
+/// The least time between updates of the minimap, in seconds.
+pub const MINIMAP_INTERVAL: f32 = 0.5;
+
+// The size of the minimap on screen, in pixels.
+const MINIMAP_PIXELS: f32 = 192.0;
+
+// Public struct `Minimap` holding the image of the minimap and what it was last rendered for.
+#[derive(Resource)]
+pub struct Minimap {
+    pub image: Handle<Image>,
+    pub timer: Timer,
+    // The column the minimap was centered on when it was last rendered.
+    pub center: Option<[i32; 2]>,
+    // Whether chunks were loaded or changed since the minimap was last rendered.
+    pub stale: bool,
+}
+
+// This system creates the minimap image and shows it in the top right corner of the screen.
+pub fn setup_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: MINIMAP_SIZE as u32,
+        height: MINIMAP_SIZE as u32,
+        depth_or_array_layers: 1,
+    };
+    let image = images.add(Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+    ));
+
+    commands.spawn(ImageBundle {
+        image: UiImage::new(image.clone()),
+        style: Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            width: Val::Px(MINIMAP_PIXELS),
+            height: Val::Px(MINIMAP_PIXELS),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    commands.insert_resource(Minimap {
+        image,
+        timer: Timer::new(
+            Duration::from_secs_f32(MINIMAP_INTERVAL),
+            TimerMode::Repeating,
+        ),
+        center: None,
+        stale: true,
+    });
+}
+
+// This system renders the map around the camera into the minimap, when the camera moved to another column
+// or chunks were loaded or changed, at most once per `MINIMAP_INTERVAL`. The camera is marked in white.
+pub fn minimap_handler(
+    time: Res<Time>,
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+    mut chunk_load_event: EventReader<ChunkLoadEvent>,
+    world: Res<VoxelWorld>,
+    camera: Query<&GlobalTransform, With<Camera3d>>,
+) {
+    if chunk_load_event.read().count() > 0 {
+        minimap.stale = true;
+    }
+
+    minimap.timer.tick(time.delta());
+    if !minimap.timer.just_finished() {
+        return;
+    }
+    let Ok(transform) = camera.get_single() else {
+        return;
+    };
+
+    let voxel = world.config.voxel_at(transform.translation());
+    let center = [voxel.x, voxel.z];
+    if !minimap.stale && minimap.center == Some(center) {
+        return;
+    }
+    minimap.stale = false;
+    minimap.center = Some(center);
+
+    let half = MINIMAP_SIZE as i32 / 2;
+    let Some(mut map) = world.render_map(
+        [center[0] - half, center[1] - half],
+        [center[0] + half - 1, center[1] + half - 1],
+    ) else {
+        return;
+    };
+    let middle = half as usize * (MINIMAP_SIZE + 1);
+    map.pixels[middle] = [255; 4];
+
+    if let Some(image) = images.get_mut(&minimap.image) {
+        image.data = map.pixels.into_iter().flatten().collect();
+    }
+}
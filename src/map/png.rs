@@ -0,0 +1,176 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use super::MapImage;
+
+// The bytes every PNG file starts with.
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+// The most bytes a stored deflate block can hold.
+const MAX_BLOCK: usize = 65535;
+
+// Function `crc32` computes the checksum of a PNG chunk.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// Function `adler32` computes the checksum of zlib data.
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in bytes {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// Function `write_chunk` writes a PNG chunk with its length and checksum.
+fn write_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    let mut checked = Vec::with_capacity(4 + data.len());
+    checked.extend_from_slice(kind);
+    checked.extend_from_slice(data);
+    writer.write_all(&checked)?;
+    writer.write_all(&crc32(&checked).to_be_bytes())
+}
+
+impl MapImage {
+    /// Public method `write_png` writes the map as an RGBA PNG image.
+    /// The pixels are stored without compression, which keeps the encoder small and needs no dependencies.
+    pub fn write_png(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&SIGNATURE)?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGBA, default compression, filtering and no interlacing.
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        write_chunk(writer, b"IHDR", &header)?;
+
+        // Every row starts with the filter type, none.
+        let mut rows = Vec::with_capacity(self.height * (1 + self.width * 4));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            rows.push(0);
+            rows.extend(row.iter().flatten());
+        }
+
+        let mut data = vec![0x78, 0x01];
+        let blocks = rows.chunks(MAX_BLOCK).count();
+        for (i, block) in rows.chunks(MAX_BLOCK).enumerate() {
+            data.push((i + 1 == blocks) as u8);
+            data.extend_from_slice(&(block.len() as u16).to_le_bytes());
+            data.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+            data.extend_from_slice(block);
+        }
+        data.extend_from_slice(&adler32(&rows).to_be_bytes());
+        write_chunk(writer, b"IDAT", &data)?;
+
+        write_chunk(writer, b"IEND", &[])
+    }
+
+    // Public method `save_png` writes the map to a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_png(&mut writer)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function `chunks` splits a PNG file into the kind and data of its chunks, checking their checksums.
+    fn chunks(bytes: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert_eq!(bytes[..8], SIGNATURE);
+        let mut chunks = Vec::new();
+        let mut rest = &bytes[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let checked = &rest[4..8 + length];
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc32(checked), crc);
+            chunks.push((checked[..4].try_into().unwrap(), checked[4..].to_vec()));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn pixels_are_stored_row_by_row() {
+        let image = MapImage {
+            width: 2,
+            height: 2,
+            pixels: vec![
+                [1, 2, 3, 255],
+                [4, 5, 6, 255],
+                [7, 8, 9, 0],
+                [10, 11, 12, 128],
+            ],
+        };
+        let mut bytes = Vec::new();
+        image.write_png(&mut bytes).unwrap();
+
+        let chunks = chunks(&bytes);
+        let kinds: Vec<&[u8; 4]> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 2, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+
+        // A single stored deflate block holding every row after its filter type, followed by the checksum.
+        let rows = [
+            0, 1, 2, 3, 255, 4, 5, 6, 255, //
+            0, 7, 8, 9, 0, 10, 11, 12, 128,
+        ];
+        let data = &chunks[1].1;
+        assert_eq!(data[..2], [0x78, 0x01]);
+        assert_eq!(data[2..7], [1, 18, 0, !18, 0xff]);
+        assert_eq!(data[7..7 + rows.len()], rows);
+        assert_eq!(data[7 + rows.len()..], adler32(&rows).to_be_bytes());
+    }
+
+    #[test]
+    fn large_images_are_split_into_blocks() {
+        let image = MapImage {
+            width: 200,
+            height: 200,
+            pixels: vec![[50; 4]; 200 * 200],
+        };
+        let mut bytes = Vec::new();
+        image.write_png(&mut bytes).unwrap();
+
+        // Rows of 801 bytes fill two blocks of the most bytes they can hold and a last one with the rest.
+        let data = &chunks(&bytes)[1].1;
+        let mut rest = &data[2..data.len() - 4];
+        let mut blocks = Vec::new();
+        while !rest.is_empty() {
+            let length = u16::from_le_bytes([rest[1], rest[2]]) as usize;
+            blocks.push((rest[0], length));
+            rest = &rest[5 + length..];
+        }
+        assert_eq!(
+            blocks,
+            [
+                (0, MAX_BLOCK),
+                (0, MAX_BLOCK),
+                (1, 801 * 200 - 2 * MAX_BLOCK)
+            ]
+        );
+    }
+}
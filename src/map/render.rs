@@ -0,0 +1,220 @@
+use crate::{
+    mesh::{ChunkMesh, Voxel},
+    util::{ChunkPos, LocalPos, WorldVoxelPos},
+    world::VoxelWorld,
+};
+
+/// How much a voxel of height difference to the neighbors brightens or darkens a pixel of the map.
+pub const HILLSHADE: f32 = 0.08;
+
+/// The most pixels a map may have, so oversized regions are rejected before anything is allocated.
+pub const MAX_MAP_PIXELS: usize = 1 << 24;
+
+// Public struct `MapImage` holding the RGBA pixels of a top-down map, row by row.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MapImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+// Public function `map_size` returns the width and height of the map of the columns between two corners,
+// both included, or `None` if it has more than `MAX_MAP_PIXELS` pixels.
+pub fn map_size(a: [i32; 2], b: [i32; 2]) -> Option<[usize; 2]> {
+    let width = a[0].abs_diff(b[0]) as usize + 1;
+    let height = a[1].abs_diff(b[1]) as usize + 1;
+    width
+        .checked_mul(height)
+        .filter(|pixels| *pixels <= MAX_MAP_PIXELS)
+        .map(|_| [width, height])
+}
+
+impl VoxelWorld {
+    /// Public method `surface` finds the highest voxel that isn't empty in every column between two corners,
+    /// given as world voxel `[x, z]` positions, both included. Columns are ordered x first, then z.
+    /// Only generated chunks are searched, columns without any voxels are `None`.
+    /// Returns `None` if there are more columns than `MAX_MAP_PIXELS`.
+    pub fn surface(&self, a: [i32; 2], b: [i32; 2]) -> Option<Vec<Option<(i32, Voxel)>>> {
+        let [width, height] = map_size(a, b)?;
+        let min = [a[0].min(b[0]), a[1].min(b[1])];
+        let max = [a[0].max(b[0]), a[1].max(b[1])];
+        let size = self.config.chunk_size;
+        let (first, _) = self.config.split(WorldVoxelPos::new(min[0], 0, min[1]));
+        let (last, _) = self.config.split(WorldVoxelPos::new(max[0], 0, max[1]));
+
+        // The generated chunks of every chunk column, from the top down.
+        let mut columns: Vec<(ChunkPos, &ChunkMesh)> = self
+            .chunks
+            .iter()
+            .filter(|(position, _)| {
+                (first.x..=last.x).contains(&position.x) && (first.z..=last.z).contains(&position.z)
+            })
+            .map(|(position, chunk)| (*position, chunk))
+            .collect();
+        columns.sort_by_key(|(position, _)| -position.y);
+
+        let mut surface = vec![None; width * height];
+        for (position, chunk) in columns {
+            for z in 0..size {
+                for x in 0..size {
+                    let column = self.config.join(position, LocalPos::new(x, 0, z));
+                    if column.x < min[0]
+                        || column.x > max[0]
+                        || column.z < min[1]
+                        || column.z > max[1]
+                    {
+                        continue;
+                    }
+
+                    let index = (column.x - min[0]) as usize + width * (column.z - min[1]) as usize;
+                    if surface[index].is_some() {
+                        continue;
+                    }
+                    surface[index] = (0..size).rev().find_map(|y| {
+                        let local = LocalPos::new(x, y, z);
+                        let [px, py, pz] = local.padded();
+                        let voxel = chunk.get(px, py, pz);
+                        (voxel != Voxel::Empty)
+                            .then(|| (self.config.join(position, local).y, voxel))
+                    });
+                }
+            }
+        }
+
+        Some(surface)
+    }
+
+    /// Public method `render_map` renders a top-down map of the columns between two corners,
+    /// given as world voxel `[x, z]` positions, both included. Every pixel takes the color of the highest voxel
+    /// of its column, brightened or darkened by how much higher it is than the columns to its west and north.
+    /// Columns without any voxels are transparent. Returns `None` if the map has more than `MAX_MAP_PIXELS` pixels.
+    pub fn render_map(&self, a: [i32; 2], b: [i32; 2]) -> Option<MapImage> {
+        let [width, height] = map_size(a, b)?;
+        let surface = self.surface(a, b)?;
+
+        let pixels = (0..width * height)
+            .map(|i| {
+                let Some((top, voxel)) = surface[i] else {
+                    return [0; 4];
+                };
+                let (x, z) = (i % width, i / width);
+                let height_at = |index: usize| surface[index].map_or(top, |(y, _)| y);
+                let west = if x > 0 { height_at(i - 1) } else { top };
+                let north = if z > 0 { height_at(i - width) } else { top };
+
+                let slope = ((top - west) + (top - north)) as f32;
+                let shade = (1.0 + slope.clamp(-4.0, 4.0) * HILLSHADE).max(0.0);
+                let color = voxel.color().unwrap_or_default();
+                let channel = |value: u8| (value as f32 * shade).round().min(255.0) as u8;
+                [
+                    channel(color.red),
+                    channel(color.green),
+                    channel(color.blue),
+                    255,
+                ]
+            })
+            .collect();
+
+        Some(MapImage {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{light::Emission, util::Color, world::WorldConfig};
+
+    const GRASS: Voxel = Voxel::Opaque(Color::new(100, 150, 50));
+    const ROCK: Voxel = Voxel::Opaque(Color::new(120, 120, 120));
+
+    // Function `world` creates a single chunk of 8 voxels with a surface of grass 2 voxels high, rising to
+    // a surface of rock 4 voxels high from x = 4 on, and no voxels in the columns with z = 7.
+    fn world() -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        world.insert(chunk, &Emission::default());
+        for z in 0..7 {
+            for x in 0..8 {
+                let (top, voxel) = if x < 4 { (1, GRASS) } else { (3, ROCK) };
+                for y in 0..=top {
+                    world.set_quietly(WorldVoxelPos::new(x, y, z), voxel);
+                }
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn surface_finds_the_highest_voxel_of_every_column() {
+        let surface = world().surface([2, 5], [5, 7]).unwrap();
+        assert_eq!(surface.len(), 4 * 3);
+        // Columns are ordered x first, then z.
+        assert_eq!(surface[0], Some((1, GRASS)));
+        assert_eq!(surface[1], Some((1, GRASS)));
+        assert_eq!(surface[2], Some((3, ROCK)));
+        assert_eq!(surface[4 + 3], Some((3, ROCK)));
+        // Columns without voxels and outside the generated chunks are both empty.
+        assert!(surface[8..].iter().all(Option::is_none));
+        assert_eq!(world().surface([-3, 0], [-1, 0]).unwrap(), vec![None; 3]);
+    }
+
+    #[test]
+    fn hillshade_brightens_steps_up_and_darkens_steps_down() {
+        let world = world();
+        let map = world.render_map([2, 0], [5, 7]).unwrap();
+        assert_eq!([map.width, map.height], [4, 8]);
+
+        // Flat columns take the color of their voxel, the first column of the step up is brightened.
+        let pixel = |x: usize, z: usize| map.pixels[x + map.width * z];
+        assert_eq!(pixel(0, 3), [100, 150, 50, 255]);
+        assert_eq!(pixel(1, 3), [100, 150, 50, 255]);
+        let bright = (120.0 * (1.0 + 2.0 * HILLSHADE)).round() as u8;
+        assert_eq!(pixel(2, 3), [bright, bright, bright, 255]);
+        assert_eq!(pixel(3, 3), [120, 120, 120, 255]);
+        // Columns without voxels are transparent.
+        assert_eq!(pixel(0, 7), [0; 4]);
+
+        // The corners can be given in any order.
+        let map = world.render_map([5, 0], [2, 0]).unwrap();
+        assert_eq!(map.pixels, world.render_map([2, 0], [5, 0]).unwrap().pixels);
+
+        // Swapping the heights of the two sides turns the step into a step down.
+        let mut flipped = world;
+        for z in 0..7 {
+            for x in 0..8 {
+                let top = if x < 4 { 3 } else { 1 };
+                for y in top + 1..=3 {
+                    flipped.set_quietly(WorldVoxelPos::new(x, y, z), Voxel::Empty);
+                }
+                for y in 0..=top {
+                    flipped.set_quietly(WorldVoxelPos::new(x, y, z), ROCK);
+                }
+            }
+        }
+        let map = flipped.render_map([3, 3], [4, 3]).unwrap();
+        let dark = (120.0 * (1.0 - 2.0 * HILLSHADE)).round() as u8;
+        assert_eq!(
+            map.pixels,
+            vec![[120, 120, 120, 255], [dark, dark, dark, 255]]
+        );
+    }
+
+    #[test]
+    fn oversized_maps_are_rejected() {
+        let world = world();
+        assert_eq!(map_size([0, 0], [0, 0]), Some([1, 1]));
+        assert_eq!(map_size([3, -2], [-1, 2]), Some([5, 5]));
+        assert_eq!(map_size([i32::MIN, 0], [i32::MAX, 0]), None);
+        assert_eq!(map_size([0, 0], [4096, 4096]), None);
+        assert!(world
+            .surface([i32::MIN, i32::MIN], [i32::MAX, i32::MAX])
+            .is_none());
+        assert!(world.render_map([i32::MIN, 0], [i32::MAX, 0]).is_none());
+    }
+}
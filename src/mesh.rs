@@ -1,31 +1,78 @@
+pub mod buffers;
 pub mod chunk;
+pub mod connectivity;
+#[cfg(feature = "bevy")]
 pub mod event;
 pub mod face;
+#[cfg(feature = "bevy")]
 pub mod generation;
+pub mod lod;
 pub mod quad;
+pub mod settings;
 pub mod side;
+pub mod surface_nets;
+#[cfg(feature = "bevy")]
+pub mod texture;
 pub mod voxel;
 
-use bevy::app::{App, Plugin, Update};
-pub use chunk::{Chunk, ChunkMesh, LoadedChunks, CHUNK_SIZE};
-pub use event::{
-    chunk_load_event_handler, chunk_unload_event_handler, ChunkLoadEvent, ChunkUnloadEvent,
+#[cfg(feature = "bevy")]
+use bevy::{
+    app::{App, Plugin, Startup, Update},
+    asset::load_internal_asset,
+    ecs::schedule::IntoSystemConfigs,
+    pbr::MaterialPlugin,
+    render::render_resource::Shader,
 };
+pub use buffers::{MeshBuffers, TextureLayers};
+pub use chunk::{ChunkMesh, CHUNK_SIZE};
+pub use connectivity::Connectivity;
 pub use face::Face;
-pub use generation::mesher;
+pub use lod::{Downsample, Lod, DOWNSAMPLE, LOD_DISTANCE, MAX_LOD};
 pub use quad::{Quad, QuadGroups};
+pub use settings::{GenerationSettings, MeshAlgorithm, MeshSettings};
 pub use side::{Axis, Side};
 pub use voxel::{Voxel, VOXEL_SIZE};
+#[cfg(feature = "bevy")]
+pub use {
+    chunk::{Chunk, LoadedChunks},
+    event::{
+        chunk_load_event_handler, chunk_unload_event_handler, ChunkLoadEvent, ChunkUnloadEvent,
+    },
+    generation::{generator, mesher, ChunkGeometry, ChunkSide},
+    texture::{
+        block_textures_handler, setup_block_textures, texture_array, BlockTextures, VoxelMaterial,
+    },
+};
+
+#[cfg(feature = "bevy")]
+use crate::{block::BlockRegistry, settings::VoxelSettings, world::despawn_handler};
 
+#[cfg(feature = "bevy")]
 pub struct MeshPlugin;
 
+#[cfg(feature = "bevy")]
 impl Plugin for MeshPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<LoadedChunks>()
+        load_internal_asset!(
+            app,
+            texture::BLOCK_SHADER_HANDLE,
+            "mesh/texture.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins(MaterialPlugin::<VoxelMaterial>::default())
+            .init_resource::<BlockRegistry>()
+            .init_resource::<LoadedChunks>()
+            .init_resource::<MeshSettings>()
+            .init_resource::<VoxelSettings>()
             .add_event::<ChunkLoadEvent>()
             .add_event::<ChunkUnloadEvent>()
+            .add_systems(Startup, setup_block_textures)
+            .add_systems(Update, block_textures_handler)
+            .add_systems(Update, generator.before(chunk_load_event_handler))
             .add_systems(Update, chunk_load_event_handler)
             .add_systems(Update, chunk_unload_event_handler)
-            .add_systems(Update, mesher);
+            // Side meshes are spawned as children, so chunks must not be despawned underneath the mesher.
+            .add_systems(Update, mesher.before(despawn_handler));
     }
 }
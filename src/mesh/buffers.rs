@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+#[cfg(feature = "bevy")]
+use bevy::render::{
+    mesh::{Indices, Mesh},
+    render_resource::PrimitiveTopology,
+};
+use rayon::iter::ParallelIterator;
+
+use crate::util::Color;
+
+#[cfg(feature = "bevy")]
+use super::texture::ATTRIBUTE_LAYER;
+use super::Face;
+
+/// The texture layers of every textured block, indexed by the color of the block and the side of the face.
+/// Layer 0 is plain white, so blocks without textures only show their color.
+pub type TextureLayers = Arc<HashMap<Color, [u32; 6]>>;
+
+// Public struct `MeshBuffers` holding the vertex and index buffers of a mesh.
+#[derive(Default, Clone, Debug)]
+pub struct MeshBuffers {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub layers: Vec<u32>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshBuffers {
+    // Public method `from_faces` builds the buffers for all the faces of the iterator, `voxel_size` world units
+    // per voxel, looking up the texture layer of every face in `layers`.
+    pub fn from_faces<'a>(
+        faces: impl ParallelIterator<Item = Face<'a>>,
+        layers: &TextureLayers,
+        voxel_size: f32,
+    ) -> Self {
+        let buffers = Arc::new(Mutex::new(Self::default()));
+
+        // For each face, extend the positions, indices, normals, colors, uvs and layers vectors
+        faces.for_each(|face| {
+            let layer = layers
+                .get(&face.quad.color)
+                .map(|layers| layers[face.side.index()])
+                .unwrap_or_default();
+
+            buffers.lock().unwrap().push_face(&face, layer, voxel_size);
+        });
+
+        Arc::try_unwrap(buffers).unwrap().into_inner().unwrap()
+    }
+
+    // Public method `push_face` appends the vertices and indices of a single face, `voxel_size` world units per voxel.
+    pub fn push_face(&mut self, face: &Face, layer: u32, voxel_size: f32) {
+        let start = self.positions.len() as u32;
+
+        self.indices.extend_from_slice(&face.indices(start));
+        self.positions
+            .extend_from_slice(&face.positions(voxel_size));
+        self.normals.extend_from_slice(&face.normals());
+        self.colors.extend_from_slice(&face.colors());
+        self.uvs.extend_from_slice(&face.tiled_uvs());
+        self.layers.extend_from_slice(&[layer; 4]);
+    }
+
+    // Public method `append` adds the geometry of other buffers, moving their vertices by `offset`.
+    pub fn append(&mut self, other: &MeshBuffers, offset: [f32; 3]) {
+        let start = self.positions.len() as u32;
+
+        self.indices
+            .extend(other.indices.iter().map(|index| index + start));
+        self.positions.extend(
+            other
+                .positions
+                .iter()
+                .map(|[x, y, z]| [x + offset[0], y + offset[1], z + offset[2]]),
+        );
+        self.normals.extend_from_slice(&other.normals);
+        self.colors.extend_from_slice(&other.colors);
+        self.uvs.extend_from_slice(&other.uvs);
+        self.layers.extend_from_slice(&other.layers);
+    }
+
+    // Public method `is_empty` checks if the buffers contain any geometry.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+// Implement the `From` trait for converting `MeshBuffers` into a `Mesh`
+#[cfg(feature = "bevy")]
+impl From<MeshBuffers> for Mesh {
+    fn from(buffers: MeshBuffers) -> Self {
+        // Create a new mesh with `PrimitiveTopology::TriangleList`
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+
+        mesh.set_indices(Some(Indices::U32(buffers.indices)));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, buffers.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, buffers.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, buffers.colors);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, buffers.uvs);
+        mesh.insert_attribute(ATTRIBUTE_LAYER, buffers.layers);
+
+        mesh
+    }
+}
@@ -1,171 +1,587 @@
-use std::sync::{Arc, Mutex};
-
-use bevy::{
-    ecs::{component::Component, entity::Entity, system::Resource},
-    utils::HashMap,
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
 };
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::{component::Component, entity::Entity, system::Resource};
 use noise::{NoiseFn, Perlin};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 
 use crate::{
+    block::{SAND, SNOW, STONE, WATER},
+    light::Light,
     mesh::Quad,
-    util::{Color, Visibility, EMPTY, OPAQUE, TRANSPARENT},
-    Position,
+    util::{ChunkPos, LocalPos, Visibility, WorldVoxelPos, EMPTY, OPAQUE, TRANSPARENT},
+    world::WorldConfig,
 };
 
-use super::{QuadGroups, Voxel};
+use super::{Connectivity, Downsample, GenerationSettings, Lod, QuadGroups, Side, Voxel};
 
-pub const CHUNK_SIZE: f32 = 48.0;
+/// The default number of voxels along each axis of a chunk, see `WorldConfig`.
+pub const CHUNK_SIZE: usize = 48;
 
+#[cfg(feature = "bevy")]
 #[derive(Resource, Default)]
-pub struct LoadedChunks(pub HashMap<Position, Entity>);
+pub struct LoadedChunks(pub HashMap<ChunkPos, Entity>);
 
+#[cfg(feature = "bevy")]
 #[derive(Component, Eq, PartialEq)]
 pub struct Chunk {
-    pub position: Position,
+    pub position: ChunkPos,
+    pub lod: Lod,
+    pub connectivity: Connectivity,
 }
 
+#[cfg(feature = "bevy")]
 impl Chunk {
-    pub fn new(position: Position) -> Self {
-        Self { position }
+    pub fn new(position: ChunkPos, lod: Lod, connectivity: Connectivity) -> Self {
+        Self {
+            position,
+            lod,
+            connectivity,
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct ChunkMesh {
     pub voxels: Vec<Voxel>,
-    pub position: Position,
+    // The light of every voxel, including the padding. Empty until the light is computed.
+    pub light: Vec<Light>,
+    // The scheduled updates of the chunk, mapping local voxel positions to the block tick they are due at.
+    pub updates: HashMap<LocalPos, u64>,
+    pub position: ChunkPos,
+    // The chunk size and voxel scale of the world the chunk belongs to.
+    pub config: WorldConfig,
 }
 
 impl ChunkMesh {
-    pub const X: usize = CHUNK_SIZE as usize + 2;
-    pub const Y: usize = CHUNK_SIZE as usize + 2;
-    pub const Z: usize = CHUNK_SIZE as usize + 2;
-
-    pub fn new(position: Position) -> Self {
+    pub fn new(position: ChunkPos, config: WorldConfig) -> Self {
+        let padded = config.padded();
         Self {
-            voxels: Vec::with_capacity(Self::size()),
+            voxels: Vec::with_capacity(padded * padded * padded),
+            light: Vec::new(),
+            updates: HashMap::new(),
             position,
+            config,
         }
     }
 
-    pub fn size() -> usize {
-        Self::X * Self::Y * Self::Z
+    // Public method `padded` returns the number of voxels along each axis, including the padding.
+    pub fn padded(&self) -> usize {
+        self.config.padded()
+    }
+
+    pub fn size(&self) -> usize {
+        self.padded() * self.padded() * self.padded()
     }
 
-    pub fn linearize(x: usize, y: usize, z: usize) -> usize {
-        x + Self::X * (y + Self::Y * z)
+    pub fn linearize(&self, x: usize, y: usize, z: usize) -> usize {
+        x + self.padded() * (y + self.padded() * z)
     }
 
-    pub fn delinearize(index: usize) -> (usize, usize, usize) {
-        let (x, index) = (index % Self::X, index / Self::X);
-        let (y, z) = (index % Self::X, index / Self::Y);
+    pub fn delinearize(&self, index: usize) -> (usize, usize, usize) {
+        let padded = self.padded();
+        let (x, index) = (index % padded, index / padded);
+        let (y, z) = (index % padded, index / padded);
         (x, y, z)
     }
 
     pub fn get(&self, x: usize, y: usize, z: usize) -> Voxel {
-        self.voxels[Self::linearize(x, y, z)]
+        self.voxels[self.linearize(x, y, z)]
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.voxels.iter().all(|voxel| *voxel == Voxel::Empty)
+    pub fn set(&mut self, x: usize, y: usize, z: usize, voxel: Voxel) {
+        let index = self.linearize(x, y, z);
+        self.voxels[index] = voxel;
     }
 
-    pub fn populate(&mut self, seed: u32) {
-        let perlin = Perlin::new(seed);
-        for i in 0..ChunkMesh::size() {
-            let (local_x, local_y, local_z) = ChunkMesh::delinearize(i);
-
-            let (x, y, z) = (
-                local_x as i32 + &self.position.x * CHUNK_SIZE as i32,
-                local_y as i32 + &self.position.y * CHUNK_SIZE as i32,
-                local_z as i32 + &self.position.z * CHUNK_SIZE as i32,
-            );
+    // Public method `light_at` returns the light of a voxel, chunks without computed light are fully lit.
+    pub fn light_at(&self, x: usize, y: usize, z: usize) -> Light {
+        self.light
+            .get(self.linearize(x, y, z))
+            .copied()
+            .unwrap_or(Light(u16::MAX))
+    }
 
-            let scale1 = 0.1;
-            let scale2 = 0.01;
-            let scale3 = 0.001;
+    pub fn set_light(&mut self, x: usize, y: usize, z: usize, light: Light) {
+        let index = self.linearize(x, y, z);
+        self.light[index] = light;
+    }
 
-            let val = perlin.get([x as f64 * scale1, z as f64 * scale1])
-                + perlin.get([x as f64 * scale2, z as f64 * scale2])
-                + perlin.get([x as f64 * scale3, z as f64 * scale3]);
+    pub fn is_empty(&self) -> bool {
+        self.voxels.iter().all(|voxel| *voxel == Voxel::Empty)
+    }
 
-            let val = val * 5.0;
+    pub fn populate(&mut self, settings: &GenerationSettings) {
+        let perlin = Perlin::new(settings.seed);
+        let origin = self.config.chunk_origin(self.position);
+        for i in 0..self.size() {
+            let (x, y, z) = self.delinearize(i);
 
-            if y <= val as i32 {
-                if y <= 3 {
-                    self.voxels.push(Voxel::Opaque(Color::new(242, 231, 122)));
-                } else if y <= 20 {
-                    self.voxels.push(Voxel::Opaque(Color::new(146, 142, 133)));
-                } else {
-                    self.voxels.push(Voxel::Opaque(Color::new(255, 250, 250)));
-                }
-            } else {
-                self.voxels.push(Voxel::Empty);
-            }
+            // The world voxel position of the padded voxel, the padding lies in the neighboring chunks.
+            let position = origin + [x as i32 - 1, y as i32 - 1, z as i32 - 1];
+            self.voxels.push(terrain(&perlin, settings, position));
         }
     }
 
     pub fn generate_mesh(&self) -> QuadGroups {
         let buffer = Arc::new(Mutex::new(QuadGroups::default()));
 
-        (0..ChunkMesh::size()).into_par_iter().for_each(|i| {
-            let (x, y, z) = ChunkMesh::delinearize(i);
-            if (x > 0 && x < ChunkMesh::X - 1)
-                && (y > 0 && y < ChunkMesh::Y - 1)
-                && (z > 0 && z < ChunkMesh::Z - 1)
-            {
+        let padded = self.padded();
+        (0..self.size()).into_par_iter().for_each(|i| {
+            let (x, y, z) = self.delinearize(i);
+            if (x > 0 && x < padded - 1) && (y > 0 && y < padded - 1) && (z > 0 && z < padded - 1) {
                 let voxel = self.get(x, y, z);
 
                 match voxel.visibility() {
-                    Visibility::Empty => return,
-                    visibility => {
+                    Visibility::Empty => {}
+                    _ => {
                         let neighbors = [
-                            self.get(x - 1, y, z),
-                            self.get(x + 1, y, z),
-                            self.get(x, y - 1, z),
-                            self.get(x, y + 1, z),
-                            self.get(x, y, z - 1),
-                            self.get(x, y, z + 1),
+                            (x - 1, y, z),
+                            (x + 1, y, z),
+                            (x, y - 1, z),
+                            (x, y + 1, z),
+                            (x, y, z - 1),
+                            (x, y, z + 1),
                         ];
 
                         neighbors
                             .into_par_iter()
                             .enumerate()
-                            .for_each(|(i, neighbor)| {
-                                let other = neighbor.visibility();
-
-                                let generate = match (visibility, other) {
-                                    (OPAQUE, EMPTY)
-                                    | (OPAQUE, TRANSPARENT)
-                                    | (TRANSPARENT, EMPTY) => true,
-
-                                    (TRANSPARENT, TRANSPARENT) => voxel != neighbor,
-
-                                    (_, _) => false,
-                                };
-
-                                match voxel {
-                                    Voxel::Opaque(color) | Voxel::Transparent(color) => {
-                                        if generate {
-                                            let mut buffer = buffer.lock().unwrap();
-                                            buffer.groups[i].push(Quad {
-                                                voxel: [x, y, z],
-                                                color,
-                                            });
-                                        }
-                                    }
-                                    _ => {}
+                            .for_each(|(i, (nx, ny, nz))| match voxel {
+                                // Faces are lit by the light of the voxel in front of them.
+                                Voxel::Opaque(color)
+                                | Voxel::Transparent(color)
+                                | Voxel::Liquid(color, _)
+                                    if visible_face(voxel, self.get(nx, ny, nz)) =>
+                                {
+                                    let mut buffer = buffer.lock().unwrap();
+                                    buffer.groups[i].push(Quad {
+                                        voxel: [x, y, z],
+                                        size: 1,
+                                        color,
+                                        light: self.light_at(nx, ny, nz),
+                                    });
                                 }
+                                _ => {}
                             });
                     }
                 }
             }
         });
 
-        let mut out = QuadGroups::default();
-        out.groups = buffer.lock().unwrap().groups.clone();
+        let groups = buffer.lock().unwrap().groups.clone();
+        QuadGroups { groups }
+    }
+
+    // Public method `connectivity` flood fills the non-opaque voxels of the chunk
+    // to find out which faces of the chunk can see each other.
+    pub fn connectivity(&self) -> Connectivity {
+        let size = self.config.chunk_size;
+        let index = |x: usize, y: usize, z: usize| x + size * (y + size * z);
+        let opaque = |x: usize, y: usize, z: usize| {
+            self.get(x + 1, y + 1, z + 1).visibility() == Visibility::Opaque
+        };
 
-        out
+        let mut connectivity = Connectivity::default();
+        let mut visited = vec![false; size * size * size];
+        let mut stack = Vec::new();
+
+        for i in 0..visited.len() {
+            let (x, y, z) = (i % size, (i / size) % size, i / (size * size));
+            if visited[i] || opaque(x, y, z) {
+                continue;
+            }
+
+            // Flood fill the open area starting at this voxel and record which faces it touches.
+            let mut faces = 0u8;
+            visited[i] = true;
+            stack.push((x, y, z));
+
+            while let Some((x, y, z)) = stack.pop() {
+                let neighbors = [
+                    (x > 0).then(|| (x - 1, y, z)),
+                    (x < size - 1).then(|| (x + 1, y, z)),
+                    (y > 0).then(|| (x, y - 1, z)),
+                    (y < size - 1).then(|| (x, y + 1, z)),
+                    (z > 0).then(|| (x, y, z - 1)),
+                    (z < size - 1).then(|| (x, y, z + 1)),
+                ];
+
+                for (side, neighbor) in neighbors.into_iter().enumerate() {
+                    match neighbor {
+                        // Leaving the chunk means the area touches that face.
+                        None => faces |= 1 << side,
+                        Some((x, y, z)) => {
+                            if !visited[index(x, y, z)] && !opaque(x, y, z) {
+                                visited[index(x, y, z)] = true;
+                                stack.push((x, y, z));
+                            }
+                        }
+                    }
+                }
+            }
+
+            connectivity.connect_faces(faces);
+        }
+
+        connectivity
+    }
+
+    pub fn generate_mesh_lod(&self, lod: Lod, downsample: Downsample) -> QuadGroups {
+        // Full resolution doesn't need any merging.
+        if lod.is_full() {
+            return self.generate_mesh();
+        }
+
+        let factor = lod.factor();
+        let cells = self.config.chunk_size / factor;
+        // The coarse grid keeps a padding of one cell, sampled from the one voxel of padding.
+        let dim = cells + 2;
+
+        // Map a coarse cell to the range of padded voxels it covers.
+        let range = |cell: usize| match cell {
+            0 => 0..1,
+            cell if cell == dim - 1 => self.padded() - 1..self.padded(),
+            cell => 1 + (cell - 1) * factor..1 + cell * factor,
+        };
+
+        let coarse: Vec<(Voxel, bool)> = (0..dim * dim * dim)
+            .into_par_iter()
+            .map(|i| {
+                let (x, y, z) = (i % dim, (i / dim) % dim, i / (dim * dim));
+                let (xs, ys, zs) = (range(x), range(y), range(z));
+
+                // Remember if the whole block is opaque so skirts can be skipped when buried.
+                let opaque = zs.clone().all(|z| {
+                    ys.clone().all(|y| {
+                        xs.clone()
+                            .all(|x| self.get(x, y, z).visibility() == Visibility::Opaque)
+                    })
+                });
+
+                (downsample.sample(self, xs, ys, zs), opaque)
+            })
+            .collect();
+
+        let get = |x: usize, y: usize, z: usize| coarse[x + dim * (y + dim * z)];
+
+        let buffer = Arc::new(Mutex::new(QuadGroups::default()));
+
+        (0..cells * cells * cells).into_par_iter().for_each(|i| {
+            let (x, y, z) = (
+                i % cells + 1,
+                (i / cells) % cells + 1,
+                i / (cells * cells) + 1,
+            );
+            let (voxel, _) = get(x, y, z);
+
+            let color = match voxel {
+                Voxel::Opaque(color) | Voxel::Transparent(color) | Voxel::Liquid(color, _) => color,
+                Voxel::Empty => return,
+            };
+
+            let neighbors = [
+                (x - 1, y, z),
+                (x + 1, y, z),
+                (x, y - 1, z),
+                (x, y + 1, z),
+                (x, y, z - 1),
+                (x, y, z + 1),
+            ];
+
+            for (i, (nx, ny, nz)) in neighbors.into_iter().enumerate() {
+                let (neighbor, opaque) = get(nx, ny, nz);
+                let border = nx == 0
+                    || nx == dim - 1
+                    || ny == 0
+                    || ny == dim - 1
+                    || nz == 0
+                    || nz == dim - 1;
+
+                // Faces on the chunk border act as skirts: they are always generated unless the
+                // neighboring chunk is solid there, hiding cracks against chunks at another level of detail.
+                let generate = if border {
+                    !opaque
+                } else {
+                    visible_face(voxel, neighbor)
+                };
+
+                if generate {
+                    let voxel = [
+                        1 + (x - 1) * factor,
+                        1 + (y - 1) * factor,
+                        1 + (z - 1) * factor,
+                    ];
+
+                    // Light the face with the brightest voxel of the layer in front of it.
+                    let side = Side::from(i);
+                    let axis = i / 2;
+                    let mut light = Light::default();
+                    for a in 0..factor {
+                        for b in 0..factor {
+                            let mut front = voxel;
+                            front[(axis + 1) % 3] += a;
+                            front[(axis + 2) % 3] += b;
+                            front[axis] = if side.positive {
+                                front[axis] + factor
+                            } else {
+                                front[axis] - 1
+                            };
+                            light = light.max(self.light_at(front[0], front[1], front[2]));
+                        }
+                    }
+
+                    let mut buffer = buffer.lock().unwrap();
+                    buffer.groups[i].push(Quad {
+                        voxel,
+                        size: factor,
+                        color,
+                        light,
+                    });
+                }
+            }
+        });
+
+        let groups = buffer.lock().unwrap().groups.clone();
+
+        QuadGroups { groups }
+    }
+}
+
+// Function `terrain` returns the generated voxel at a world voxel position.
+fn terrain(perlin: &Perlin, settings: &GenerationSettings, position: WorldVoxelPos) -> Voxel {
+    let WorldVoxelPos { x, y, z } = position;
+
+    let scale1 = 0.1;
+    let scale2 = 0.01;
+    let scale3 = 0.001;
+
+    let val = perlin.get([x as f64 * scale1, z as f64 * scale1])
+        + perlin.get([x as f64 * scale2, z as f64 * scale2])
+        + perlin.get([x as f64 * scale3, z as f64 * scale3]);
+
+    let val = val * 5.0;
+
+    if y <= val as i32 {
+        if y <= 3 {
+            SAND
+        } else if y <= 20 {
+            STONE
+        } else {
+            SNOW
+        }
+    } else if y <= settings.sea_level {
+        // Fill everything below the sea level with water, forming oceans and lakes.
+        WATER
+    } else {
+        Voxel::Empty
+    }
+}
+
+// Function `visible_face` checks if the face of `voxel` facing `neighbor` should be generated.
+fn visible_face(voxel: Voxel, neighbor: Voxel) -> bool {
+    match (voxel.visibility(), neighbor.visibility()) {
+        (OPAQUE, EMPTY) | (OPAQUE, TRANSPARENT) | (TRANSPARENT, EMPTY) => true,
+
+        // Liquids of different levels are still the same liquid.
+        (TRANSPARENT, TRANSPARENT) => voxel.color() != neighbor.color(),
+
+        (_, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every voxel of a chunk holds the terrain of its own world voxel position.
+    #[test]
+    fn populate_samples_world_voxel_positions() {
+        let config = WorldConfig::new(8, 0.1);
+        let settings = GenerationSettings::default();
+        let perlin = Perlin::new(settings.seed);
+
+        for position in [ChunkPos::new(0, 0, 0), ChunkPos::new(-1, -1, 2)] {
+            let mut chunk = ChunkMesh::new(position, config);
+            chunk.populate(&settings);
+
+            for i in 0..chunk.size() {
+                let (x, y, z) = chunk.delinearize(i);
+                // The padding is checked against the neighbors below.
+                let Some(local) = LocalPos::from_padded([x, y, z], 8) else {
+                    continue;
+                };
+                let world = config.join(position, local);
+                assert_eq!(chunk.get(x, y, z), terrain(&perlin, &settings, world));
+            }
+        }
+    }
+
+    // The padding of a generated chunk holds the same voxels its neighbors generate inside.
+    #[test]
+    fn populated_padding_matches_neighbors() {
+        let config = WorldConfig::new(8, 0.1);
+        let settings = GenerationSettings::default();
+        let populate = |position: ChunkPos| {
+            let mut chunk = ChunkMesh::new(position, config);
+            chunk.populate(&settings);
+            chunk
+        };
+
+        let chunk = populate(ChunkPos::new(-1, -1, 2));
+        let above = populate(ChunkPos::new(-1, 0, 2));
+        let east = populate(ChunkPos::new(0, -1, 2));
+        for z in 1..=8 {
+            for y in 1..=8 {
+                assert_eq!(chunk.get(9, y, z), east.get(1, y, z));
+            }
+            for x in 1..=8 {
+                assert_eq!(chunk.get(x, 9, z), above.get(x, 1, z));
+            }
+        }
+    }
+
+    // Function `filled` creates a chunk of 8 voxels, filling every padded voxel with `fill`.
+    fn filled(fill: impl Fn(usize, usize, usize) -> Voxel) -> ChunkMesh {
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), WorldConfig::new(8, 1.0));
+        chunk.voxels = (0..chunk.size())
+            .map(|i| {
+                let (x, y, z) = chunk.delinearize(i);
+                fill(x, y, z)
+            })
+            .collect();
+        chunk
+    }
+
+    fn quads(mesh: &QuadGroups) -> [Vec<([usize; 3], usize)>; 6] {
+        mesh.groups.clone().map(|quads| {
+            let mut quads: Vec<_> = quads.iter().map(|quad| (quad.voxel, quad.size)).collect();
+            quads.sort();
+            quads
+        })
+    }
+
+    #[test]
+    fn downsampling_merges_blocks_of_voxels() {
+        // A block of 2x2x2 voxels with sand at the bottom and stone on top.
+        let chunk = filled(|x, y, z| match [x, y, z] {
+            [1 | 2, 1, 1 | 2] if x + z < 4 => SAND,
+            [1, 2, 1] | [2, 2, 2] => STONE,
+            _ => Voxel::Empty,
+        });
+        let sample = |downsample: Downsample| downsample.sample(&chunk, 1..3, 1..3, 1..3);
+        assert_eq!(sample(Downsample::Majority), SAND);
+        assert_eq!(sample(Downsample::TopSurface), STONE);
+
+        // Less than half of the block is filled, which only the top surface keeps.
+        let sample = |downsample: Downsample| downsample.sample(&chunk, 1..3, 2..4, 1..3);
+        assert_eq!(sample(Downsample::Majority), Voxel::Empty);
+        assert_eq!(sample(Downsample::TopSurface), STONE);
+    }
+
+    #[test]
+    fn lod_meshes_merge_voxels_into_larger_quads() {
+        // A single voxel, which the top surface keeps as a block of 2 voxels and the majority drops.
+        let chunk = filled(|x, y, z| match [x, y, z] {
+            [3, 3, 3] => STONE,
+            _ => Voxel::Empty,
+        });
+        let mesh = chunk.generate_mesh_lod(Lod(1), Downsample::TopSurface);
+        assert_eq!(quads(&mesh), [(); 6].map(|_| vec![([3, 3, 3], 2)]));
+        assert!(chunk
+            .generate_mesh_lod(Lod(1), Downsample::Majority)
+            .is_empty());
+
+        // At level 2 blocks of 4 voxels are merged, starting at the first voxel inside the padding.
+        let mesh = chunk.generate_mesh_lod(Lod(2), Downsample::TopSurface);
+        assert_eq!(quads(&mesh), [(); 6].map(|_| vec![([1, 1, 1], 4)]));
+    }
+
+    #[test]
+    fn lod_meshes_have_skirts_unless_buried() {
+        // Ground 4 voxels high, with the neighbor to the west one voxel lower.
+        let chunk = filled(|x, y, _| match (x, y) {
+            (0, y) if y <= 3 => STONE,
+            (x, y) if x > 0 && y <= 4 => STONE,
+            _ => Voxel::Empty,
+        });
+        let [west, east, below, above, north, south] =
+            quads(&chunk.generate_mesh_lod(Lod(1), Downsample::TopSurface));
+
+        // The top of the ground is merged into blocks of 2 voxels.
+        assert_eq!(above.len(), 16);
+        assert!(above
+            .iter()
+            .all(|(voxel, size)| voxel[1] == 3 && *size == 2));
+
+        // The downsampled neighbor to the west is solid where the ground is, but not entirely,
+        // so a skirt covers the gap a mesh of the neighbor at another level of detail could leave.
+        // Neighbors that are entirely solid bury the faces.
+        let skirt: Vec<_> = [1, 3, 5, 7].map(|z| ([1, 3, z], 2)).into();
+        assert_eq!(west, skirt);
+        assert!(east.is_empty() && below.is_empty() && north.is_empty() && south.is_empty());
+
+        // At full resolution the neighbor hides every face but the step.
+        let mesh = chunk.generate_mesh_lod(Lod(0), Downsample::TopSurface);
+        assert_eq!(mesh.groups[0].len(), 8);
+        assert!(mesh.groups[0]
+            .iter()
+            .all(|quad| quad.voxel[0] == 1 && quad.voxel[1] == 4));
+    }
+
+    fn connected_pairs(connectivity: Connectivity) -> Vec<(usize, usize)> {
+        (0..6)
+            .flat_map(|a| (a..6).map(move |b| (a, b)))
+            .filter(|(a, b)| connectivity.connected(*a, *b))
+            .collect()
+    }
+
+    #[test]
+    fn connectivity_of_solid_and_empty_chunks() {
+        // Solid chunks connect nothing, whatever their padding holds.
+        let solid = filled(|x, y, z| {
+            if [x, y, z].contains(&0) {
+                Voxel::Empty
+            } else {
+                STONE
+            }
+        });
+        assert_eq!(solid.connectivity(), Connectivity::default());
+
+        // Every face of an empty chunk sees every other face.
+        let empty = filled(|_, _, _| Voxel::Empty);
+        let all: Vec<_> = (0..6).flat_map(|a| (a..6).map(move |b| (a, b))).collect();
+        assert_eq!(connected_pairs(empty.connectivity()), all);
+        // Transparent voxels and liquid don't block the view.
+        let clear = filled(|_, _, _| WATER);
+        assert_eq!(clear.connectivity(), empty.connectivity());
+    }
+
+    #[test]
+    fn connectivity_follows_tunnels() {
+        // A straight tunnel from west to east, and a pocket touching the bottom only.
+        let chunk = filled(|x, y, z| match [x, y, z] {
+            [_, 4, 4] => Voxel::Empty,
+            [2, 1, 7] => Voxel::Empty,
+            _ => STONE,
+        });
+        assert_eq!(
+            connected_pairs(chunk.connectivity()),
+            [(0, 0), (0, 1), (1, 1), (2, 2)]
+        );
+
+        // A bend upwards in the middle of the tunnel connects the top as well.
+        let chunk = filled(|x, y, z| match [x, y, z] {
+            [_, 4, 4] => Voxel::Empty,
+            [4, y, 4] if y > 4 => Voxel::Empty,
+            _ => STONE,
+        });
+        assert_eq!(
+            connected_pairs(chunk.connectivity()),
+            [(0, 0), (0, 1), (0, 3), (1, 1), (1, 3), (3, 3)]
+        );
     }
 }
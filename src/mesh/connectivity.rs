@@ -0,0 +1,31 @@
+// Public struct `Connectivity` records which faces of a chunk can see each other
+// through non-opaque voxels. Faces are indexed the same way as `Side`.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Connectivity(pub u64);
+
+impl Connectivity {
+    // A chunk where every face is connected to every other face, e.g. an empty chunk.
+    pub const ALL: Self = Self(u64::MAX);
+
+    // Public method `connect` marks the faces `a` and `b` as connected.
+    pub fn connect(&mut self, a: usize, b: usize) {
+        self.0 |= 1 << (a * 6 + b);
+        self.0 |= 1 << (b * 6 + a);
+    }
+
+    // Public method `connected` checks if the faces `a` and `b` are connected.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.0 & (1 << (a * 6 + b)) != 0
+    }
+
+    // Public method `connect_faces` connects every pair of faces in the bitmask `faces`.
+    pub fn connect_faces(&mut self, faces: u8) {
+        for a in 0..6 {
+            for b in 0..6 {
+                if faces & (1 << a) != 0 && faces & (1 << b) != 0 {
+                    self.connect(a, b);
+                }
+            }
+        }
+    }
+}
@@ -1,87 +1,132 @@
-use std::sync::{Arc, Mutex};
-
 use bevy::{
     ecs::{
         event::{Event, EventReader},
-        system::{Commands, ResMut},
-    },
-    render::{
-        mesh::{Indices, Mesh},
-        render_resource::PrimitiveTopology,
+        system::{Commands, Res, ResMut},
     },
     tasks::AsyncComputeTaskPool,
 };
-use rayon::iter::ParallelIterator;
 
 use crate::{
-    mesh::{generation::ComputeTransform, ChunkMesh},
-    util::Position,
-    LoadedChunks,
+    block::BlockRegistry,
+    mesh::{
+        generation::{ComputeTransform, GenerateChunk},
+        texture::BlockTextures,
+        Chunk, ChunkGeometry, ChunkMesh, LoadedChunks, Lod, MeshAlgorithm, MeshBuffers,
+        MeshSettings, DOWNSAMPLE,
+    },
+    settings::VoxelSettings,
+    util::ChunkPos,
+    world::VoxelWorld,
 };
 
 #[derive(Event)]
 pub struct ChunkLoadEvent {
-    pub position: Position,
+    pub position: ChunkPos,
+    pub lod: Lod,
 }
 
+// Bevy systems take their resources as arguments, so this one needs more than clippy likes.
+#[allow(clippy::too_many_arguments)]
 pub fn chunk_load_event_handler(
     mut commands: Commands, // Commands for spawning entities and components
     mut chunk_load_event: EventReader<ChunkLoadEvent>, // Reader for `ChunkLoadEvent` events
     mut loaded_chunks: ResMut<LoadedChunks>, // Mutable reference to `LoadedChunks` resource
+    settings: Res<MeshSettings>, // Options used when meshing chunks
+    voxel_settings: Res<VoxelSettings>, // Options used when generating chunks
+    textures: Res<BlockTextures>, // Texture layers of the blocks
+    world: Res<VoxelWorld>, // Voxels of the generated chunks
+    registry: Res<BlockRegistry>, // Definitions of the blocks, for the light they emit
 ) {
     let thread_pool = AsyncComputeTaskPool::get(); // Get the async compute task pool
 
     // Iterate over each `ChunkLoadEvent` event
     for event in chunk_load_event.read() {
-        let mut chunk_mesh = ChunkMesh::new(event.position); // Create a new `ChunkMesh` at the event position
-        let task = thread_pool.spawn(async move {
-            // Spawn a new task in the async compute task pool
-            chunk_mesh.populate(1); // Populate the chunk mesh
+        let lod = event.lod;
 
-            let result = chunk_mesh.generate_mesh(); // Generate the mesh for the chunk
+        // Chunks that weren't generated before are generated and lit first, they are meshed once
+        // they have been added to the world.
+        let Some(chunk_mesh) = world.chunks.get(&event.position).cloned() else {
+            let position = event.position;
+            let emission = registry.emission();
+            let generation = voxel_settings.generation();
+            let config = world.config;
+            let task = thread_pool.spawn(async move {
+                let mut chunk_mesh = ChunkMesh::new(position, config);
+                chunk_mesh.populate(&generation); // Populate the chunk mesh
+                chunk_mesh.compute_light(&emission); // Light the chunk on its own
+                chunk_mesh
+            });
 
-            // If the result is empty, return early with the chunk position
-            if result.is_empty() {
-                return (None, chunk_mesh.position);
+            match loaded_chunks
+                .0
+                .get(&event.position)
+                .and_then(|entity| commands.get_entity(*entity))
+            {
+                Some(mut entity) => {
+                    entity.try_insert(GenerateChunk(task, lod));
+                }
+                None => {
+                    // Spawn a `GenerateChunk` entity with the task and insert it into the `loaded_chunks` resource
+                    let id = commands.spawn(GenerateChunk(task, lod)).id();
+                    loaded_chunks.0.insert(event.position, id);
+                }
             }
+            continue;
+        };
 
-            // Create new thread-safe vectors for positions, indices, normals, and colors
-            let positions = Arc::new(Mutex::new(Vec::new()));
-            let indices = Arc::new(Mutex::new(Vec::new()));
-            let normals = Arc::new(Mutex::new(Vec::new()));
-            let colors = Arc::new(Mutex::new(Vec::new()));
-
-            // For each face in the result, extend the positions, indices, normals, and colors vectors
-            result.iter().for_each(|face| {
-                let mut positions = positions.lock().unwrap();
-                let mut indices = indices.lock().unwrap();
-                let mut normals = normals.lock().unwrap();
-                let mut colors = colors.lock().unwrap();
+        let settings = *settings;
+        let layers = textures.layers.clone();
+        let task = thread_pool.spawn(async move {
+            // Spawn a new task in the async compute task pool
+            let chunk = Chunk::new(chunk_mesh.position, lod, chunk_mesh.connectivity());
 
-                indices.extend_from_slice(&face.indices(positions.len() as u32));
-                positions.extend_from_slice(&face.positions());
-                normals.extend_from_slice(&face.normals());
-                colors.extend_from_slice(&face.colors());
-            });
+            // Smooth terrain is always meshed at full resolution as a single mesh
+            if settings.algorithm == MeshAlgorithm::SurfaceNets {
+                let buffers = chunk_mesh.generate_surface_nets();
+                if buffers.is_empty() {
+                    return (None, chunk);
+                }
+                return (Some(ChunkGeometry::Single(buffers.into())), chunk);
+            }
 
-            // Create a new mesh with `PrimitiveTopology::TriangleList`
-            let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+            let result = chunk_mesh.generate_mesh_lod(lod, DOWNSAMPLE); // Generate the mesh for the chunk at its level of detail
 
-            // Unwrap the thread-safe vectors and insert them into the mesh
-            let indices = Arc::try_unwrap(indices).unwrap().into_inner().unwrap();
-            let positions = Arc::try_unwrap(positions).unwrap().into_inner().unwrap();
-            let normals = Arc::try_unwrap(normals).unwrap().into_inner().unwrap();
-            let colors = Arc::try_unwrap(colors).unwrap().into_inner().unwrap();
+            // If the result is empty, return early with the chunk
+            if result.is_empty() {
+                return (None, chunk);
+            }
 
-            mesh.set_indices(Some(Indices::U32(indices)));
-            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+            // Build one mesh per side if requested, skipping sides without any faces
+            let geometry = if settings.split_sides {
+                ChunkGeometry::Sides(Box::new(std::array::from_fn(|side| {
+                    let buffers = MeshBuffers::from_faces(
+                        result.iter_side(side),
+                        &layers,
+                        chunk_mesh.config.voxel_size,
+                    );
+                    (!buffers.is_empty()).then(|| buffers.into())
+                })))
+            } else {
+                ChunkGeometry::Single(
+                    MeshBuffers::from_faces(result.iter(), &layers, chunk_mesh.config.voxel_size)
+                        .into(),
+                )
+            };
 
-            // Return the mesh and the chunk position
-            (Some(mesh), chunk_mesh.position)
+            // Return the geometry and the chunk
+            (Some(geometry), chunk)
         });
 
+        // If the chunk is already loaded, remesh it in place so it stays visible until the new mesh is ready
+        if let Some(mut entity) = loaded_chunks
+            .0
+            .get(&event.position)
+            .and_then(|entity| commands.get_entity(*entity))
+        {
+            entity.try_insert(ComputeTransform(task));
+            continue;
+        }
+
         // Spawn a `ComputeTransform` entity with the task and insert it into the `loaded_chunks` resource
         let id = commands.spawn(ComputeTransform(task)).id();
         loaded_chunks.0.insert(event.position, id);
@@ -3,20 +3,28 @@ use bevy::ecs::{
     system::{Commands, ResMut},
 };
 
-use crate::{util::Position, world::Despawn, LoadedChunks};
+use crate::{
+    mesh::LoadedChunks,
+    util::ChunkPos,
+    world::{Despawn, VoxelWorld},
+};
 
 #[derive(Event)]
 pub struct ChunkUnloadEvent {
-    pub position: Position,
+    pub position: ChunkPos,
 }
 
 pub fn chunk_unload_event_handler(
     mut commands: Commands, // Commands for spawning entities and components
     mut chunk_unload_event: EventReader<ChunkUnloadEvent>, // Reader for `ChunkUnloadEvent` events
     mut loaded_chunks: ResMut<LoadedChunks>, // Mutable reference to `LoadedChunks` resource
+    mut world: ResMut<VoxelWorld>, // Voxels of the generated chunks
 ) {
     // Iterate over each `ChunkUnloadEvent` event
     for event in chunk_unload_event.read() {
+        // Forget the voxels of the chunk, they will be generated again when it is loaded, along with any stored edits
+        world.unload(event.position);
+
         // If the `LoadedChunks` resource contains the event position, remove it
         if let Some(entity) = loaded_chunks.0.remove(&event.position) {
             // If the entity exists, insert a `Despawn` component
@@ -1,4 +1,4 @@
-use super::{side::Axis, Quad, Side, VOXEL_SIZE};
+use super::{side::Axis, Quad, Side};
 
 pub struct Face<'a> {
     // The `side` field represents the side of a voxel.
@@ -9,9 +9,19 @@ pub struct Face<'a> {
 
 // Implement methods for `Face`.
 impl<'a> Face<'a> {
-    // Public method `colors` returns a vector of RGBA color values.
+    // Public method `colors` returns a vector of RGBA color values, tinted by the light falling on the face.
     pub fn colors(&self) -> Vec<[f32; 4]> {
-        vec![self.quad.color.as_linear_rgba(); 4]
+        let [light_red, light_green, light_blue] = self.quad.light.color();
+        let [red, green, blue, alpha] = self.quad.color.as_linear_rgba();
+        vec![
+            [
+                red * light_red,
+                green * light_green,
+                blue * light_blue,
+                alpha
+            ];
+            4
+        ]
     }
 
     // Public method `indices` returns an array of indices.
@@ -25,8 +35,9 @@ impl<'a> Face<'a> {
     /// The `side` field determines the orientation of the face, and the `quad` field provides the position of the voxel.
     /// The method first determines the relative positions of the vertices based on the `side` field.
     /// Then it calculates the absolute positions of the vertices based on the `voxel` field of the `quad` field.
-    /// Finally, it adds the relative positions to the absolute position of the voxel to get the absolute positions of the vertices.
-    pub fn positions(&self) -> [[f32; 3]; 4] {
+    /// Finally, it adds the relative positions to the absolute position of the voxel to get the absolute positions of the vertices,
+    /// scaled by `voxel_size` world units per voxel.
+    pub fn positions(&self, voxel_size: f32) -> [[f32; 3]; 4] {
         // Determine the relative positions of the vertices based on the `side` field.
         let positions = match (&self.side.axis, &self.side.positive) {
             (Axis::X, false) => [
@@ -67,37 +78,38 @@ impl<'a> Face<'a> {
             ],
         };
 
+        // Quads spanning multiple voxels are centered on the block of voxels they cover.
+        let size = self.quad.size as f32;
+        let offset = (size - 1.0) / 2.0;
+
         // Calculate the absolute position of the voxel based on the `voxel` field of the `quad` field.
         let (x, y, z) = (
-            (self.quad.voxel[0] - 1) as f32,
-            (self.quad.voxel[1] - 1) as f32,
-            (self.quad.voxel[2] - 1) as f32,
+            (self.quad.voxel[0] - 1) as f32 + offset,
+            (self.quad.voxel[1] - 1) as f32 + offset,
+            (self.quad.voxel[2] - 1) as f32 + offset,
         );
 
-        // Scale the voxel grid to the desired size.
-        let voxel_size = VOXEL_SIZE;
-
         // Add the relative positions to the absolute position of the voxel to get the absolute positions of the vertices.
         [
             [
-                x * voxel_size + positions[0][0] * voxel_size,
-                y * voxel_size + positions[0][1] * voxel_size,
-                z * voxel_size + positions[0][2] * voxel_size,
+                x * voxel_size + positions[0][0] * size * voxel_size,
+                y * voxel_size + positions[0][1] * size * voxel_size,
+                z * voxel_size + positions[0][2] * size * voxel_size,
             ],
             [
-                x * voxel_size + positions[1][0] * voxel_size,
-                y * voxel_size + positions[1][1] * voxel_size,
-                z * voxel_size + positions[1][2] * voxel_size,
+                x * voxel_size + positions[1][0] * size * voxel_size,
+                y * voxel_size + positions[1][1] * size * voxel_size,
+                z * voxel_size + positions[1][2] * size * voxel_size,
             ],
             [
-                x * voxel_size + positions[2][0] * voxel_size,
-                y * voxel_size + positions[2][1] * voxel_size,
-                z * voxel_size + positions[2][2] * voxel_size,
+                x * voxel_size + positions[2][0] * size * voxel_size,
+                y * voxel_size + positions[2][1] * size * voxel_size,
+                z * voxel_size + positions[2][2] * size * voxel_size,
             ],
             [
-                x * voxel_size + positions[3][0] * voxel_size,
-                y * voxel_size + positions[3][1] * voxel_size,
-                z * voxel_size + positions[3][2] * voxel_size,
+                x * voxel_size + positions[3][0] * size * voxel_size,
+                y * voxel_size + positions[3][1] * size * voxel_size,
+                z * voxel_size + positions[3][2] * size * voxel_size,
             ],
         ]
     }
@@ -118,6 +130,14 @@ impl<'a> Face<'a> {
         }
     }
 
+    // Public method `tiled_uvs` returns UV coordinates that repeat once per voxel the quad spans.
+    // Faces pointing sideways are flipped vertically so textures stay upright.
+    pub fn tiled_uvs(&self) -> [[f32; 2]; 4] {
+        let size = self.quad.size as f32;
+        let flip_v = self.side.axis != Axis::Y;
+        self.uvs(false, flip_v).map(|[u, v]| [u * size, v * size])
+    }
+
     // Public method `voxel` returns the voxel of the `Quad`.
     pub fn voxel(&self) -> [usize; 3] {
         self.quad.voxel
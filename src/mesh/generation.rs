@@ -1,67 +1,152 @@
 use futures_lite::future;
 
 use bevy::{
-    asset::Assets,
+    asset::{Assets, Handle},
     ecs::{
         component::Component,
         entity::Entity,
-        system::{Commands, Query, ResMut},
+        event::EventWriter,
+        system::{Commands, Query, Res, ResMut},
     },
-    math::Vec3,
-    pbr::{PbrBundle, StandardMaterial},
-    render::mesh::Mesh,
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    pbr::MaterialMeshBundle,
+    render::{mesh::Mesh, prelude::SpatialBundle},
     tasks::{block_on, Task},
     transform::components::Transform,
 };
 
-use crate::{world::Despawn, Position};
+use crate::{
+    block::BlockRegistry,
+    world::{Despawn, VoxelWorld},
+};
+
+use super::{
+    texture::{BlockTextures, VoxelMaterial},
+    Chunk, ChunkLoadEvent, ChunkMesh, LoadedChunks, Lod, Side,
+};
 
-use super::{Chunk, CHUNK_SIZE, VOXEL_SIZE};
+// Public enum `ChunkGeometry` holding either one mesh for the whole chunk or one mesh per side.
+pub enum ChunkGeometry {
+    Single(Mesh),
+    Sides(Box<[Option<Mesh>; 6]>),
+}
 
-// Public struct `ComputeTransform` that wraps a `Task` which returns an `Option<Mesh>` and a `Position`.
+// Public struct `ChunkSide` marks the child entity holding the mesh for one side of a chunk.
 #[derive(Component)]
-pub struct ComputeTransform(pub Task<(Option<Mesh>, Position)>);
+pub struct ChunkSide(pub Side);
+
+// Public struct `ComputeTransform` that wraps a `Task` which returns an `Option<ChunkGeometry>` and a `Chunk`.
+#[derive(Component)]
+pub struct ComputeTransform(pub Task<(Option<ChunkGeometry>, Chunk)>);
+
+// Public struct `GenerateChunk` that wraps a `Task` which generates and lights the voxels of a new chunk,
+// along with the level of detail to mesh it at once it is added to the world.
+#[derive(Component)]
+pub struct GenerateChunk(pub Task<ChunkMesh>, pub Lod);
+
+// Public function `generator` that adds generated chunks to the world and requests their meshes.
+// Chunks are only meshed once they are in the world, so their light includes that of their neighbors.
+pub fn generator(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut GenerateChunk)>,
+    loaded_chunks: Res<LoadedChunks>,
+    registry: Res<BlockRegistry>,
+    mut world: ResMut<VoxelWorld>,
+    mut chunk_load_event: EventWriter<ChunkLoadEvent>,
+) {
+    let emission = registry.emission();
+
+    for (entity, mut task) in tasks.iter_mut() {
+        let Some(chunk_mesh) = block_on(future::poll_once(&mut task.0)) else {
+            continue;
+        };
+        commands.entity(entity).remove::<GenerateChunk>();
+
+        // Drop the voxels if the chunk was unloaded in the meantime.
+        let position = chunk_mesh.position;
+        if loaded_chunks.0.get(&position) != Some(&entity) || world.chunks.contains_key(&position) {
+            continue;
+        }
+
+        world.insert(chunk_mesh, &emission);
+        // The chunk is meshed right away, only its neighbors need remeshing if their light changed.
+        world.dirty.remove(&position);
+        chunk_load_event.send(ChunkLoadEvent {
+            position,
+            lod: task.1,
+        });
+    }
+}
 
 // Public function `mesher` that processes `ComputeTransform` tasks.
 pub fn mesher(
     mut commands: Commands,
     mut tasks: Query<(Entity, &mut ComputeTransform)>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    textures: Res<BlockTextures>,
+    world: Res<VoxelWorld>,
 ) {
     // Iterate over each `ComputeTransform` task.
     for (entity, mut task) in tasks.iter_mut() {
         // If the task is ready and returns a result,
-        if let Some((mesh, position)) = block_on(future::poll_once(&mut task.0)) {
+        if let Some((geometry, chunk)) = block_on(future::poll_once(&mut task.0)) {
             // Remove the `ComputeTransform` component from the entity.
             commands.entity(entity).remove::<ComputeTransform>();
             // Get the entity from the commands.
             let entity = commands.get_entity(entity);
             // If the entity exists,
             if let Some(mut entity) = entity {
+                // Remove the side meshes of a previous mesh, they are rebuilt below if needed.
+                entity.despawn_descendants();
+
+                let transform =
+                    Transform::from_translation(world.config.chunk_translation(chunk.position));
+
                 // Depending on whether the mesh exists or not,
-                match mesh {
-                    // If the mesh exists,
-                    Some(mesh) => {
-                        // Insert a `PbrBundle` and a `Chunk` into the entity.
+                match geometry {
+                    // If there is a single mesh,
+                    Some(ChunkGeometry::Single(mesh)) => {
+                        // Insert a `MaterialMeshBundle` and a `Chunk` into the entity.
                         entity
-                            .try_insert(PbrBundle {
+                            .try_insert(MaterialMeshBundle::<VoxelMaterial> {
                                 mesh: meshes.add(mesh),
-                                material: materials.add(StandardMaterial::default()),
-                                transform: Transform::from_translation(Vec3::new(
-                                    position.x as f32 * CHUNK_SIZE * VOXEL_SIZE,
-                                    position.y as f32 * CHUNK_SIZE * VOXEL_SIZE,
-                                    position.z as f32 * CHUNK_SIZE * VOXEL_SIZE,
-                                )),
+                                material: textures.material.clone(),
+                                transform,
                                 ..Default::default()
                             })
-                            .try_insert(Chunk::new(position));
+                            .try_insert(chunk);
                     }
-                    // If the mesh does not exist,
-                    None => {
+                    // If there is a mesh per side,
+                    Some(ChunkGeometry::Sides(sides)) => {
+                        // Insert a `SpatialBundle` and a `Chunk` into the entity and a `MaterialMeshBundle` per side into its children.
+                        entity
+                            .remove::<Handle<Mesh>>()
+                            .try_insert(SpatialBundle::from_transform(transform))
+                            .try_insert(chunk)
+                            .with_children(|parent| {
+                                for (side, mesh) in sides.into_iter().enumerate() {
+                                    if let Some(mesh) = mesh {
+                                        parent.spawn((
+                                            MaterialMeshBundle::<VoxelMaterial> {
+                                                mesh: meshes.add(mesh),
+                                                material: textures.material.clone(),
+                                                ..Default::default()
+                                            },
+                                            ChunkSide(side.into()),
+                                        ));
+                                    }
+                                }
+                            });
+                    }
+                    // If the mesh does not exist at full resolution, the chunk is empty at every level of detail,
+                    None if chunk.lod.is_full() => {
                         // Insert a `Despawn` into the entity.
                         entity.try_insert(Despawn);
                     }
+                    // Otherwise keep the chunk around without a mesh so it can be remeshed when it gets closer.
+                    None => {
+                        entity.remove::<Handle<Mesh>>().try_insert(chunk);
+                    }
                 }
             }
         }
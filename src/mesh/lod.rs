@@ -0,0 +1,113 @@
+use std::ops::Range;
+
+use super::{ChunkMesh, Voxel};
+
+/// The highest level of detail that will be selected.
+/// At level 3 every 8x8x8 block of voxels is merged into one.
+pub const MAX_LOD: u8 = 3;
+
+/// The distance in chunks at which the first level of detail kicks in.
+/// Every doubling of this distance drops one more level of detail.
+pub const LOD_DISTANCE: f64 = 2.0;
+
+/// The rule used to merge voxels for chunks that are not meshed at full resolution.
+pub const DOWNSAMPLE: Downsample = Downsample::TopSurface;
+
+// Public struct `Lod` holding the level of detail of a chunk.
+// Level 0 is full resolution, each level above that halves the resolution.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Hash)]
+pub struct Lod(pub u8);
+
+impl Lod {
+    // Public method `from_distance` selects the level of detail for a chunk at the given distance in chunks.
+    pub fn from_distance(distance: f64) -> Self {
+        if distance < LOD_DISTANCE {
+            return Self(0);
+        }
+
+        // Drop one level every time the distance doubles, capped at `MAX_LOD`.
+        let level = (distance / LOD_DISTANCE).log2().floor() as u8 + 1;
+        Self(level.min(MAX_LOD))
+    }
+
+    // Public method `factor` returns the number of voxels merged along each axis.
+    pub fn factor(&self) -> usize {
+        1 << self.0
+    }
+
+    // Public method `is_full` checks if this level of detail is full resolution.
+    pub fn is_full(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+// Public enum `Downsample` describing how a block of voxels is merged into one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Downsample {
+    // The block is solid if at least half of its voxels are, using the most common voxel.
+    Majority,
+    // The block is solid if any of its voxels are, using the top-most voxel.
+    TopSurface,
+}
+
+impl Downsample {
+    // Public method `sample` merges the voxels of `chunk` within the given padded ranges into one voxel.
+    pub fn sample(
+        &self,
+        chunk: &ChunkMesh,
+        xs: Range<usize>,
+        ys: Range<usize>,
+        zs: Range<usize>,
+    ) -> Voxel {
+        match self {
+            Self::Majority => {
+                let total = xs.len() * ys.len() * zs.len();
+                let mut counts: Vec<(Voxel, usize)> = Vec::new();
+
+                // Count how often each non-empty voxel occurs in the block.
+                for z in zs {
+                    for y in ys.clone() {
+                        for x in xs.clone() {
+                            let voxel = chunk.get(x, y, z);
+                            if voxel == Voxel::Empty {
+                                continue;
+                            }
+
+                            match counts.iter_mut().find(|(other, _)| *other == voxel) {
+                                Some((_, count)) => *count += 1,
+                                None => counts.push((voxel, 1)),
+                            }
+                        }
+                    }
+                }
+
+                // If less than half of the block is filled it is considered empty.
+                let solid: usize = counts.iter().map(|(_, count)| count).sum();
+                if solid * 2 < total {
+                    return Voxel::Empty;
+                }
+
+                counts
+                    .into_iter()
+                    .max_by_key(|(_, count)| *count)
+                    .map(|(voxel, _)| voxel)
+                    .unwrap_or_default()
+            }
+            Self::TopSurface => {
+                // Walk the block from the top down and return the first voxel that isn't empty.
+                for y in ys.rev() {
+                    for z in zs.clone() {
+                        for x in xs.clone() {
+                            let voxel = chunk.get(x, y, z);
+                            if voxel != Voxel::Empty {
+                                return voxel;
+                            }
+                        }
+                    }
+                }
+
+                Voxel::Empty
+            }
+        }
+    }
+}
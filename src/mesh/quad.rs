@@ -1,6 +1,6 @@
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::util::Color;
+use crate::{light::Light, util::Color};
 
 use super::Face;
 
@@ -8,8 +8,12 @@ use super::Face;
 pub struct Quad {
     // The `voxel` field represents the position of the quad in a 3D space.
     pub voxel: [usize; 3],
+    // The `size` field represents the number of voxels the quad spans along each axis.
+    pub size: usize,
     // The `color` field represents the color of the quad.
     pub color: Color,
+    // The `light` field represents the light falling on the quad.
+    pub light: Light,
 }
 
 // Public struct `QuadGroups` with a `Default` trait.
@@ -24,7 +28,7 @@ pub struct QuadGroups {
 impl QuadGroups {
     // Public method `iter` returns a parallel iterator over `Face` items.
     // Each `Face` item is created from a `Quad` and its corresponding side index.
-    pub fn iter(&self) -> impl ParallelIterator<Item = Face> {
+    pub fn iter(&self) -> impl ParallelIterator<Item = Face<'_>> {
         self.groups
             .par_iter()
             .enumerate()
@@ -35,6 +39,14 @@ impl QuadGroups {
             })
     }
 
+    // Public method `iter_side` returns a parallel iterator over the `Face` items of a single side.
+    pub fn iter_side(&self, index: usize) -> impl ParallelIterator<Item = Face<'_>> {
+        self.groups[index].par_iter().map(move |quad| Face {
+            side: index.into(),
+            quad,
+        })
+    }
+
     // Public method `is_empty` checks if all groups in `QuadGroups` are empty.
     // Returns `true` if all groups are empty, `false` otherwise.
     pub fn is_empty(&self) -> bool {
@@ -0,0 +1,43 @@
+#[cfg(feature = "bevy")]
+use bevy::ecs::system::Resource;
+
+use crate::fluid::SEA_LEVEL;
+
+// Public enum `MeshAlgorithm` selecting how the voxels of a chunk are turned into a mesh.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MeshAlgorithm {
+    // Blocky terrain made of voxel faces, supporting levels of detail and side meshes.
+    #[default]
+    Cubes,
+    // Smooth terrain extracted as an isosurface using Surface Nets.
+    SurfaceNets,
+}
+
+// Public struct `MeshSettings` holding the options used when meshing chunks.
+#[derive(Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct MeshSettings {
+    // The algorithm used to mesh the chunks of the world.
+    pub algorithm: MeshAlgorithm,
+    // Emit one mesh per side instead of one mesh per chunk, so sides facing away
+    // from the camera can be skipped for the whole chunk.
+    pub split_sides: bool,
+}
+
+// Public struct `GenerationSettings` holding the options used when generating the voxels of new chunks.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerationSettings {
+    // The seed of the noise shaping the terrain.
+    pub seed: u32,
+    // The height below which empty space is filled with water, in voxels.
+    pub sea_level: i32,
+}
+
+impl Default for GenerationSettings {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            sea_level: SEA_LEVEL,
+        }
+    }
+}
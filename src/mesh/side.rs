@@ -1,3 +1,4 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Axis {
     X,
     Y,
@@ -5,6 +6,7 @@ pub enum Axis {
 }
 
 // Public struct `Side` with fields `axis` and `positive`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Side {
     // The `axis` field represents the axis of the side.
     pub axis: Axis,
@@ -32,6 +34,33 @@ impl Side {
         }
     }
 
+    // Public method `offset` returns the direction of the side as a step on the voxel or chunk grid.
+    pub fn offset(&self) -> [i32; 3] {
+        let [x, y, z] = self.normal();
+        [x as i32, y as i32, z as i32]
+    }
+
+    // Public method `index` returns the index of the side, the inverse of `Side::from(usize)`.
+    pub fn index(&self) -> usize {
+        let axis = match self.axis {
+            Axis::X => 0,
+            Axis::Y => 2,
+            Axis::Z => 4,
+        };
+        axis + self.positive as usize
+    }
+
+    // Public method `faces_point` checks if faces of this side anywhere within the box `min`..`max`
+    // can face `point`, so whole groups of faces pointing away from it can be skipped.
+    pub fn faces_point(&self, point: [f32; 3], min: [f32; 3], max: [f32; 3]) -> bool {
+        let axis = self.index() / 2;
+        if self.positive {
+            point[axis] > min[axis]
+        } else {
+            point[axis] < max[axis]
+        }
+    }
+
     // Public method `normals` returns an array of four normal vectors of the side.
     // This can be useful for operations that require multiple normals of the same side.
     pub fn normals(&self) -> [[f32; 3]; 4] {
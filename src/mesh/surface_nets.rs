@@ -0,0 +1,256 @@
+use crate::light::Light;
+
+use super::{ChunkMesh, MeshBuffers, Voxel};
+
+// The corners of a cell, as offsets from its minimum corner.
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [0, 1, 0],
+    [1, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [0, 1, 1],
+    [1, 1, 1],
+];
+
+// The edges of a cell, as pairs of indices into `CORNERS`.
+const EDGES: [[usize; 2]; 12] = [
+    [0, 1],
+    [2, 3],
+    [4, 5],
+    [6, 7],
+    [0, 2],
+    [1, 3],
+    [4, 6],
+    [5, 7],
+    [0, 4],
+    [1, 5],
+    [2, 6],
+    [3, 7],
+];
+
+// Function `density` returns the density of a voxel, positive inside the surface and negative outside.
+fn density(voxel: Voxel) -> f32 {
+    match voxel {
+        Voxel::Empty => -1.0,
+        Voxel::Opaque(_) | Voxel::Transparent(_) | Voxel::Liquid(..) => 1.0,
+    }
+}
+
+impl ChunkMesh {
+    /// Public method `generate_surface_nets` extracts a smooth isosurface from the voxels of the chunk.
+    /// The density grid is sampled at voxel centers and every cell between eight of them that
+    /// crosses the surface gets a vertex at the average of its edge crossings.
+    /// Quads are emitted for every edge crossing the surface whose lower end lies inside the chunk,
+    /// so the cells reaching into the padding line up exactly with those of the neighboring chunks.
+    pub fn generate_surface_nets(&self) -> MeshBuffers {
+        let cells = self.padded() - 1;
+        let voxel_size = self.config.voxel_size;
+        let cell_index = |x: usize, y: usize, z: usize| x + cells * (y + cells * z);
+
+        let mut buffers = MeshBuffers::default();
+        let mut vertices = vec![u32::MAX; cells * cells * cells];
+
+        // Place a vertex in every cell that crosses the surface.
+        for z in 0..cells {
+            for y in 0..cells {
+                for x in 0..cells {
+                    let voxels = CORNERS.map(|[dx, dy, dz]| self.get(x + dx, y + dy, z + dz));
+                    let densities = voxels.map(density);
+
+                    // Skip cells that are entirely inside or outside the surface.
+                    if densities.iter().all(|d| *d > 0.0) || densities.iter().all(|d| *d <= 0.0) {
+                        continue;
+                    }
+
+                    // Average the points where the edges of the cell cross the surface.
+                    let mut sum = [0.0; 3];
+                    let mut count = 0.0;
+                    for [a, b] in EDGES {
+                        let (da, db) = (densities[a], densities[b]);
+                        if (da > 0.0) == (db > 0.0) {
+                            continue;
+                        }
+
+                        let t = da / (da - db);
+                        for axis in 0..3 {
+                            let (pa, pb) = (CORNERS[a][axis] as f32, CORNERS[b][axis] as f32);
+                            sum[axis] += pa + (pb - pa) * t;
+                        }
+                        count += 1.0;
+                    }
+
+                    // The normal points down the density gradient, away from the inside.
+                    let mut gradient = [0.0; 3];
+                    for (corner, d) in CORNERS.iter().zip(densities) {
+                        for axis in 0..3 {
+                            gradient[axis] += if corner[axis] == 1 { d } else { -d };
+                        }
+                    }
+                    let length = (gradient[0] * gradient[0]
+                        + gradient[1] * gradient[1]
+                        + gradient[2] * gradient[2])
+                        .sqrt()
+                        .max(f32::EPSILON);
+
+                    // Use the color of the highest filled corner so surfaces show their top color.
+                    let color = [6, 7, 2, 3, 4, 5, 0, 1]
+                        .into_iter()
+                        .find_map(|corner| match voxels[corner] {
+                            Voxel::Empty => None,
+                            voxel => voxel.color(),
+                        })
+                        .unwrap_or_default();
+
+                    // Light the vertex with the brightest corner, the filled corners being dark.
+                    let [light_red, light_green, light_blue] = CORNERS
+                        .iter()
+                        .map(|[dx, dy, dz]| self.light_at(x + dx, y + dy, z + dz))
+                        .fold(Light::default(), Light::max)
+                        .color();
+
+                    // Padded coordinates are shifted by one so voxel centers line up with the cube mesher.
+                    vertices[cell_index(x, y, z)] = buffers.positions.len() as u32;
+                    buffers.positions.push([
+                        (x as f32 + sum[0] / count - 1.0) * voxel_size,
+                        (y as f32 + sum[1] / count - 1.0) * voxel_size,
+                        (z as f32 + sum[2] / count - 1.0) * voxel_size,
+                    ]);
+                    buffers.normals.push([
+                        -gradient[0] / length,
+                        -gradient[1] / length,
+                        -gradient[2] / length,
+                    ]);
+                    let [red, green, blue, alpha] = color.as_linear_rgba();
+                    buffers.colors.push([
+                        red * light_red,
+                        green * light_green,
+                        blue * light_blue,
+                        alpha,
+                    ]);
+                    // Smooth terrain isn't textured, so it samples the plain white layer.
+                    buffers.uvs.push([0.0, 0.0]);
+                    buffers.layers.push(0);
+                }
+            }
+        }
+
+        // Connect the vertices of the four cells around every edge that crosses the surface.
+        let size = self.config.chunk_size;
+        for z in 1..=size {
+            for y in 1..=size {
+                for x in 1..=size {
+                    let inside = density(self.get(x, y, z)) > 0.0;
+
+                    for axis in 0..3 {
+                        // The two axes perpendicular to the edge, in right-handed order.
+                        let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+
+                        let mut end = [x, y, z];
+                        end[axis] += 1;
+                        if inside == (density(self.get(end[0], end[1], end[2])) > 0.0) {
+                            continue;
+                        }
+
+                        let cell = |db: usize, dc: usize| {
+                            let mut cell = [x, y, z];
+                            cell[b] -= db;
+                            cell[c] -= dc;
+                            vertices[cell_index(cell[0], cell[1], cell[2])]
+                        };
+                        let quad = [cell(1, 1), cell(0, 1), cell(0, 0), cell(1, 0)];
+
+                        // Wind the quad so it faces away from the inside of the surface.
+                        if inside {
+                            buffers.indices.extend_from_slice(&[
+                                quad[0], quad[1], quad[2], quad[0], quad[2], quad[3],
+                            ]);
+                        } else {
+                            buffers.indices.extend_from_slice(&[
+                                quad[0], quad[2], quad[1], quad[0], quad[3], quad[2],
+                            ]);
+                        }
+                    }
+                }
+            }
+        }
+
+        buffers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{block::STONE, util::ChunkPos, world::WorldConfig};
+
+    // Function `single_voxel` meshes a chunk of 8 voxels holding a single voxel.
+    fn single_voxel(position: [usize; 3]) -> MeshBuffers {
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), WorldConfig::new(8, 0.5));
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        chunk.set(position[0], position[1], position[2], STONE);
+        chunk.generate_surface_nets()
+    }
+
+    #[test]
+    fn single_voxels_are_closed_meshes() {
+        let buffers = single_voxel([4, 4, 4]);
+        // A vertex in each of the 8 cells around the voxel, and a quad for each of its 6 faces.
+        assert_eq!(buffers.positions.len(), 8);
+        assert_eq!(buffers.indices.len(), 6 * 6);
+
+        // Every edge is shared by exactly two triangles, winding it in opposite directions.
+        let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+        for triangle in buffers.indices.chunks(3) {
+            for i in 0..3 {
+                *edges
+                    .entry((triangle[i], triangle[(i + 1) % 3]))
+                    .or_default() += 1;
+            }
+        }
+        for (a, b) in edges.keys() {
+            assert_eq!(edges[&(*a, *b)], 1, "{a} {b}");
+            assert_eq!(edges.get(&(*b, *a)), Some(&1), "{a} {b}");
+        }
+
+        // The vertices surround the center of the voxel, in world units, with normals pointing away from it.
+        let center = [1.5; 3];
+        for (position, normal) in buffers.positions.iter().zip(buffers.normals.iter()) {
+            let offset = [0, 1, 2].map(|axis| position[axis] - center[axis]);
+            assert!(offset
+                .iter()
+                .all(|offset| offset.abs() > 0.0 && offset.abs() < 0.5));
+            let dot: f32 = (0..3).map(|axis| offset[axis] * normal[axis]).sum();
+            assert!(dot > 0.0, "{position:?} {normal:?}");
+        }
+
+        // Triangles face away from the voxel as well.
+        for triangle in buffers.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| buffers.positions[triangle[i] as usize]);
+            let (u, v) = (
+                [0, 1, 2].map(|i| b[i] - a[i]),
+                [0, 1, 2].map(|i| c[i] - a[i]),
+            );
+            let normal = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            let dot: f32 = (0..3).map(|i| (a[i] - center[i]) * normal[i]).sum();
+            assert!(dot > 0.0, "{triangle:?}");
+        }
+    }
+
+    #[test]
+    fn voxels_in_the_padding_are_left_to_the_neighbors() {
+        // Only edges whose lower end lies inside the chunk get quads, so a voxel in the padding above
+        // the chunk adds the face pointing into it, and one in the padding below is meshed by the chunk below.
+        let buffers = single_voxel([4, 9, 4]);
+        assert_eq!(buffers.indices.len(), 6);
+        assert!(buffers.normals.iter().all(|normal| normal[1] < 0.0));
+        assert!(single_voxel([4, 0, 4]).indices.is_empty());
+    }
+}
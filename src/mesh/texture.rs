@@ -0,0 +1,241 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bevy::{
+    asset::{Asset, AssetServer, Assets, Handle},
+    ecs::system::{Commands, Res, ResMut, Resource},
+    log::warn,
+    pbr::{
+        ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline,
+        StandardMaterial,
+    },
+    reflect::TypePath,
+    render::{
+        mesh::{MeshVertexAttribute, MeshVertexBufferLayout},
+        render_resource::{
+            AsBindGroup, Extent3d, RenderPipelineDescriptor, Shader, ShaderRef,
+            SpecializedMeshPipelineError, TextureDimension, TextureFormat, TextureViewDescriptor,
+            TextureViewDimension, VertexFormat,
+        },
+        texture::{Image, ImageSampler},
+    },
+};
+
+use crate::block::BlockRegistry;
+
+use super::TextureLayers;
+
+/// The vertex attribute holding the layer of the block texture array a face samples.
+pub const ATTRIBUTE_LAYER: MeshVertexAttribute =
+    MeshVertexAttribute::new("Voxel_Layer", 4_204_557_163, VertexFormat::Uint32);
+
+/// The shader location of `ATTRIBUTE_LAYER`, after the locations used by the standard mesh pipeline.
+pub const LAYER_LOCATION: u32 = 8;
+
+/// The shader sampling the block texture array on top of the standard material.
+pub const BLOCK_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x5c1e_94d2_7a3b_4f08_b6e1_2d9c_83a7_f041);
+
+/// The material used for every chunk.
+pub type VoxelMaterial = ExtendedMaterial<StandardMaterial, BlockTextureArray>;
+
+// Public struct `BlockTextureArray` extending the standard material with the block textures.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct BlockTextureArray {
+    #[texture(100, dimension = "2d_array")]
+    #[sampler(101)]
+    pub array: Handle<Image>,
+}
+
+impl MaterialExtension for BlockTextureArray {
+    fn vertex_shader() -> ShaderRef {
+        BLOCK_SHADER_HANDLE.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        BLOCK_SHADER_HANDLE.into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Append the layer to the attributes picked by the standard pipeline, shaders that don't read it ignore it.
+        let layer = layout.get_layout(&[ATTRIBUTE_LAYER.at_shader_location(LAYER_LOCATION)])?;
+        if let Some(buffer) = descriptor.vertex.buffers.first_mut() {
+            buffer.attributes.extend(layer.attributes);
+        }
+        Ok(())
+    }
+}
+
+// Public struct `BlockTextures` holding the block texture array and the material sampling it.
+#[derive(Resource)]
+pub struct BlockTextures {
+    // The texture layers of every textured block.
+    pub layers: TextureLayers,
+    // The material shared by every chunk.
+    pub material: Handle<VoxelMaterial>,
+    // The images making up the layers after the white one, in layer order.
+    pub images: Vec<Handle<Image>>,
+    // Whether the texture array has been built.
+    pub ready: bool,
+}
+
+// Public function `texture_array` creates an image of `layers` layers of the given size, bound as a texture array
+// even if it only has a single layer, as the block shader expects.
+pub fn texture_array(size: Extent3d, layers: u32, data: Vec<u8>) -> Image {
+    let mut array = Image::new(
+        Extent3d {
+            depth_or_array_layers: layers,
+            ..size
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    array.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        ..Default::default()
+    });
+    array.sampler = ImageSampler::nearest();
+    array
+}
+
+// This system starts loading the block textures and assigns every texture a layer.
+pub fn setup_block_textures(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<BlockRegistry>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<VoxelMaterial>>,
+) {
+    let mut paths: Vec<&str> = Vec::new();
+    let mut layers = HashMap::new();
+
+    for block in registry.0.iter() {
+        let (Some(textures), Some(color)) = (&block.textures, block.voxel.color()) else {
+            continue;
+        };
+
+        // Textures shared between faces or blocks only get a single layer.
+        let sides = textures.paths().map(|path| {
+            let index = match paths.iter().position(|other| *other == path) {
+                Some(index) => index,
+                None => {
+                    paths.push(path);
+                    paths.len() - 1
+                }
+            };
+            index as u32 + 1
+        });
+
+        layers.insert(color, sides);
+    }
+
+    let handles = paths
+        .into_iter()
+        .map(|path| asset_server.load(path.to_string()))
+        .collect();
+
+    // Until the textures are loaded the material samples a single white layer, so chunks meshed before then
+    // show their plain colors instead of binding a 2d texture where the shader expects an array.
+    let placeholder = texture_array(
+        Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        1,
+        vec![255; 4],
+    );
+    let material = materials.add(ExtendedMaterial {
+        base: StandardMaterial::default(),
+        extension: BlockTextureArray {
+            array: images.add(placeholder),
+        },
+    });
+
+    commands.insert_resource(BlockTextures {
+        layers: Arc::new(layers),
+        material,
+        images: handles,
+        ready: false,
+    });
+}
+
+// This system stacks the block textures into a texture array once they are all loaded.
+pub fn block_textures_handler(
+    mut textures: ResMut<BlockTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<VoxelMaterial>>,
+) {
+    if textures.ready
+        || textures
+            .images
+            .iter()
+            .any(|image| images.get(image).is_none())
+    {
+        return;
+    }
+    textures.ready = true;
+
+    // Every layer has the size of the first texture, layer 0 is plain white.
+    let size = textures
+        .images
+        .first()
+        .and_then(|image| images.get(image))
+        .map(|image| image.texture_descriptor.size)
+        .unwrap_or(Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        });
+    let white = vec![255; (size.width * size.height * 4) as usize];
+
+    let mut data = white.clone();
+    for handle in textures.images.iter() {
+        let image = images.get(handle).unwrap();
+        let descriptor = &image.texture_descriptor;
+
+        if descriptor.size != size || descriptor.format != TextureFormat::Rgba8UnormSrgb {
+            warn!(
+                "Block texture {:?} should be a {}x{} rgba image, using white instead",
+                handle.path(),
+                size.width,
+                size.height
+            );
+            data.extend_from_slice(&white);
+            continue;
+        }
+
+        data.extend_from_slice(&image.data);
+    }
+
+    let array = images.add(texture_array(size, textures.images.len() as u32 + 1, data));
+    if let Some(material) = materials.get_mut(&textures.material) {
+        material.extension.array = array;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texture_arrays_are_bound_as_arrays() {
+        let size = Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        };
+        for layers in [1, 3] {
+            let array = texture_array(size, layers, vec![255; 16 * layers as usize]);
+            assert_eq!(array.texture_descriptor.size.depth_or_array_layers, layers);
+            assert_eq!(array.texture_descriptor.dimension, TextureDimension::D2);
+            let view = array.texture_view_descriptor.unwrap();
+            assert_eq!(view.dimension, Some(TextureViewDimension::D2Array));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Renders chunks with the standard material, multiplying the base color with a layer of the block texture array.
+// The vertex stage mirrors the standard mesh vertex shader while passing the texture layer along.
+
+#import bevy_pbr::{
+    mesh_functions,
+    forward_io::{VertexOutput, FragmentOutput},
+    view_transformations::position_world_to_clip,
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    pbr_types::STANDARD_MATERIAL_FLAGS_UNLIT_BIT,
+}
+#import bevy_render::instance_index::get_instance_index
+
+@group(1) @binding(100) var block_textures: texture_2d_array<f32>;
+@group(1) @binding(101) var block_sampler: sampler;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+#ifdef VERTEX_UVS
+    @location(2) uv: vec2<f32>,
+#endif
+#ifdef VERTEX_COLORS
+    @location(5) color: vec4<f32>,
+#endif
+    @location(8) layer: u32,
+};
+
+struct BlockVertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_position: vec4<f32>,
+    @location(1) world_normal: vec3<f32>,
+#ifdef VERTEX_UVS
+    @location(2) uv: vec2<f32>,
+#endif
+#ifdef VERTEX_COLORS
+    @location(4) color: vec4<f32>,
+#endif
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    @location(5) @interpolate(flat) instance_index: u32,
+#endif
+    @location(6) @interpolate(flat) layer: u32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> BlockVertexOutput {
+    var out: BlockVertexOutput;
+
+    var model = mesh_functions::get_model_matrix(vertex.instance_index);
+
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(
+        vertex.normal,
+        get_instance_index(vertex.instance_index)
+    );
+    out.world_position = mesh_functions::mesh_position_local_to_world(model, vec4<f32>(vertex.position, 1.0));
+    out.position = position_world_to_clip(out.world_position.xyz);
+
+#ifdef VERTEX_UVS
+    out.uv = vertex.uv;
+#endif
+
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = get_instance_index(vertex.instance_index);
+#endif
+
+#ifdef BASE_INSTANCE_WORKAROUND
+    // Keeps the push constant in use, see https://github.com/bevyengine/bevy/issues/10509
+    out.position.x += min(f32(get_instance_index(0u)), 0.0);
+#endif
+
+    out.layer = vertex.layer;
+
+    return out;
+}
+
+@fragment
+fn fragment(
+    in: BlockVertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    // Rebuild the standard vertex output so the standard material functions can be used.
+    var mesh: VertexOutput;
+    mesh.position = in.position;
+    mesh.world_position = in.world_position;
+    mesh.world_normal = in.world_normal;
+#ifdef VERTEX_UVS
+    mesh.uv = in.uv;
+#endif
+#ifdef VERTEX_COLORS
+    mesh.color = in.color;
+#endif
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    mesh.instance_index = in.instance_index;
+#endif
+
+    var pbr_input = pbr_input_from_standard_material(mesh, is_front);
+
+#ifdef VERTEX_UVS
+    // Faces spanning multiple voxels have UVs beyond 1, repeat the texture across them.
+    pbr_input.material.base_color *= textureSample(block_textures, block_sampler, fract(in.uv), in.layer);
+#endif
+
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    var out: FragmentOutput;
+    if (pbr_input.material.flags & STANDARD_MATERIAL_FLAGS_UNLIT_BIT) == 0u {
+        out.color = apply_pbr_lighting(pbr_input);
+    } else {
+        out.color = pbr_input.material.base_color;
+    }
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+
+    return out;
+}
@@ -1,14 +1,16 @@
 use crate::util::{Color, Visibility};
 
-/// The size of a voxel
+/// The default size of a voxel in world units, see `WorldConfig`.
 pub const VOXEL_SIZE: f32 = 0.1;
 
-#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Hash)]
 pub enum Voxel {
     #[default]
     Empty,
     Opaque(Color),
     Transparent(Color),
+    // A liquid and its level, see `fluid::SOURCE_LEVEL`.
+    Liquid(Color, u8),
 }
 
 impl Voxel {
@@ -16,7 +18,14 @@ impl Voxel {
         match self {
             Self::Empty => Visibility::Empty,
             Self::Opaque(_) => Visibility::Opaque,
-            Self::Transparent(_) => Visibility::Transparent,
+            Self::Transparent(_) | Self::Liquid(..) => Visibility::Transparent,
+        }
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            Self::Empty => None,
+            Self::Opaque(color) | Self::Transparent(color) | Self::Liquid(color, _) => Some(*color),
         }
     }
 }
@@ -0,0 +1,21 @@
+pub mod aabb;
+#[cfg(feature = "bevy")]
+pub mod body;
+pub mod sweep;
+
+pub use aabb::Aabb;
+#[cfg(feature = "bevy")]
+use bevy::app::{App, Plugin, Update};
+#[cfg(feature = "bevy")]
+pub use body::{physics_handler, Body, GRAVITY, TERMINAL_VELOCITY};
+pub use sweep::Sweep;
+
+#[cfg(feature = "bevy")]
+pub struct PhysicsPlugin;
+
+#[cfg(feature = "bevy")]
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, physics_handler);
+    }
+}
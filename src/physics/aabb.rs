@@ -0,0 +1,142 @@
+use glam::Vec3;
+
+use crate::{util::WorldVoxelPos, world::WorldConfig};
+
+// Gap kept between touching boxes, so rounding doesn't make them overlap.
+const EPSILON: f32 = 1e-4;
+
+// Public struct `Aabb` describing an axis-aligned bounding box in world space.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    // Public method `voxel` returns the box of the voxel at a world voxel position.
+    pub fn voxel(position: WorldVoxelPos, config: &WorldConfig) -> Self {
+        let center = config.voxel_center(position);
+        let half = Vec3::splat(config.voxel_size / 2.0);
+        Self::new(center - half, center + half)
+    }
+
+    // Public method `translate` returns the box moved by `offset`.
+    pub fn translate(&self, offset: Vec3) -> Self {
+        Self::new(self.min + offset, self.max + offset)
+    }
+
+    // Public method `expand` returns the box stretched to cover it moving by `motion`.
+    pub fn expand(&self, motion: Vec3) -> Self {
+        Self::new(
+            self.min + motion.min(Vec3::ZERO),
+            self.max + motion.max(Vec3::ZERO),
+        )
+    }
+
+    // Public method `intersects` checks if the box overlaps another, boxes that only touch don't overlap.
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..3).all(|axis| self.overlaps(other, axis))
+    }
+
+    // Public method `voxels` returns the world voxel positions of every voxel the box overlaps.
+    pub fn voxels(&self, config: &WorldConfig) -> impl Iterator<Item = WorldVoxelPos> {
+        let min = (self.min / config.voxel_size + Vec3::splat(0.5 + EPSILON))
+            .floor()
+            .as_ivec3();
+        let max = (self.max / config.voxel_size + Vec3::splat(0.5 - EPSILON))
+            .floor()
+            .as_ivec3();
+
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y)
+                .flat_map(move |y| (min.x..=max.x).map(move |x| WorldVoxelPos { x, y, z }))
+        })
+    }
+
+    /// Public method `clip` shortens a motion of the box along `axis` so it stops at `other`.
+    /// Boxes that don't overlap on the other two axes or lie behind the box don't block it.
+    pub fn clip(&self, other: &Self, axis: usize, motion: f32) -> f32 {
+        let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+        if !self.overlaps(other, b) || !self.overlaps(other, c) {
+            return motion;
+        }
+
+        if motion > 0.0 && other.min[axis] >= self.max[axis] - EPSILON {
+            motion
+                .min(other.min[axis] - self.max[axis] - EPSILON)
+                .max(0.0)
+        } else if motion < 0.0 && other.max[axis] <= self.min[axis] + EPSILON {
+            motion
+                .max(other.max[axis] - self.min[axis] + EPSILON)
+                .min(0.0)
+        } else {
+            motion
+        }
+    }
+
+    // Private method `overlaps` checks if the box overlaps another along a single axis.
+    fn overlaps(&self, other: &Self, axis: usize) -> bool {
+        self.min[axis] < other.max[axis] - EPSILON && self.max[axis] > other.min[axis] + EPSILON
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(min: [f32; 3]) -> Aabb {
+        let min = Vec3::from(min);
+        Aabb::new(min, min + Vec3::ONE)
+    }
+
+    #[test]
+    fn clip_stops_at_boxes_ahead() {
+        let aabb = unit([0.0, 0.0, 0.0]);
+        let wall = unit([2.0, 0.0, 0.0]);
+
+        assert!((aabb.clip(&wall, 0, 5.0) - 1.0).abs() < 1e-3);
+        // Motions that don't reach the box or move away from it are kept.
+        assert_eq!(aabb.clip(&wall, 0, 0.5), 0.5);
+        assert_eq!(aabb.clip(&wall, 0, -5.0), -5.0);
+        // The box only blocks motion along the axis it lies on.
+        assert_eq!(aabb.clip(&wall, 1, 5.0), 5.0);
+        assert_eq!(aabb.clip(&wall, 2, -5.0), -5.0);
+    }
+
+    #[test]
+    fn clip_ignores_boxes_beside_the_path() {
+        let aabb = unit([0.0, 0.0, 0.0]);
+        assert_eq!(aabb.clip(&unit([2.0, 1.0, 0.0]), 0, 5.0), 5.0);
+        assert_eq!(aabb.clip(&unit([2.0, 0.0, -1.0]), 0, 5.0), 5.0);
+        // Boxes that partly overlap the path still block it.
+        let clipped = aabb.clip(&unit([0.5, -3.0, -0.5]), 1, -5.0);
+        assert!((clipped + 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn touching_boxes_dont_intersect() {
+        let aabb = unit([0.0, 0.0, 0.0]);
+        assert!(aabb.intersects(&unit([0.5, 0.5, 0.5])));
+        assert!(!aabb.intersects(&unit([1.0, 0.0, 0.0])));
+        assert!(!aabb.intersects(&unit([0.0, -1.0, 0.0])));
+    }
+
+    #[test]
+    fn voxels_are_scaled_by_the_voxel_size() {
+        let config = WorldConfig::new(8, 0.25);
+        let voxel = Aabb::voxel(WorldVoxelPos::new(1, -2, 0), &config);
+        assert_eq!(voxel.min, Vec3::new(0.125, -0.625, -0.125));
+        assert_eq!(voxel.max, Vec3::new(0.375, -0.375, 0.125));
+
+        // The box of a voxel only overlaps that voxel.
+        let positions: Vec<_> = voxel.voxels(&config).collect();
+        assert_eq!(positions, [WorldVoxelPos::new(1, -2, 0)]);
+
+        let aabb = Aabb::new(Vec3::ZERO, Vec3::new(0.3, 0.3, 0.1));
+        assert_eq!(aabb.voxels(&config).count(), 2 * 2);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        system::{Query, Res},
+    },
+    math::Vec3,
+    time::Time,
+    transform::components::Transform,
+};
+
+use crate::world::VoxelWorld;
+
+use super::Aabb;
+
+/// The downwards acceleration of bodies, in voxels per second squared.
+pub const GRAVITY: f32 = 32.0;
+
+/// The highest speed bodies can fall at, in voxels per second.
+pub const TERMINAL_VELOCITY: f32 = 60.0;
+
+// Public struct `Body` making an entity collide with the voxel terrain.
+#[derive(Component, Clone, Debug)]
+pub struct Body {
+    // The corners of the bounding box, relative to the translation of the entity.
+    pub min: Vec3,
+    pub max: Vec3,
+    // The velocity of the body, in world units per second.
+    pub velocity: Vec3,
+    // The height of the ledges the body climbs without jumping.
+    pub step_height: f32,
+    // Whether gravity pulls the body down.
+    pub gravity: bool,
+    // Whether the body moves through voxels instead of colliding with them.
+    pub noclip: bool,
+    // Whether the body is standing on a voxel.
+    pub grounded: bool,
+}
+
+impl Body {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self {
+            min,
+            max,
+            velocity: Vec3::ZERO,
+            step_height: 0.0,
+            gravity: true,
+            noclip: false,
+            grounded: false,
+        }
+    }
+
+    // Public method `aabb` returns the bounding box of the body at a translation.
+    pub fn aabb(&self, translation: Vec3) -> Aabb {
+        Aabb::new(translation + self.min, translation + self.max)
+    }
+
+    /// Public method `step` advances the body by `delta` seconds through the voxel world
+    /// and returns its new translation.
+    /// Velocity along the axes the body collided on is dropped, so it slides along walls and stops on the ground.
+    pub fn step(&mut self, world: &VoxelWorld, translation: Vec3, delta: f32) -> Vec3 {
+        if self.noclip {
+            self.grounded = false;
+            return translation + self.velocity * delta;
+        }
+
+        if self.gravity {
+            let scale = world.config.voxel_size;
+            self.velocity.y =
+                (self.velocity.y - GRAVITY * scale * delta).max(-TERMINAL_VELOCITY * scale);
+        }
+
+        let motion = self.velocity * delta;
+        // Only climb ledges while walking on the ground, not while jumping or falling.
+        let step_height = if self.grounded { self.step_height } else { 0.0 };
+        let sweep = world.sweep_step(self.aabb(translation), motion, step_height);
+
+        for axis in 0..3 {
+            if sweep.collided.test(axis) {
+                self.velocity[axis] = 0.0;
+            }
+        }
+        self.grounded = sweep.grounded(motion);
+
+        translation + sweep.motion
+    }
+}
+
+// This system moves every body, stopping them against the voxel terrain.
+pub fn physics_handler(
+    time: Res<Time>,
+    world: Res<VoxelWorld>,
+    mut bodies: Query<(&mut Transform, &mut Body)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (mut transform, mut body) in bodies.iter_mut() {
+        // Wait for the voxels the body could move into to be generated, so it doesn't fall through the terrain.
+        let loaded = body.noclip
+            || body
+                .aabb(transform.translation)
+                .expand(body.velocity * delta - Vec3::Y * world.config.voxel_size)
+                .voxels(&world.config)
+                .all(|position| world.get(position).is_some());
+        if !loaded {
+            continue;
+        }
+
+        transform.translation = body.step(&world, transform.translation, delta);
+    }
+}
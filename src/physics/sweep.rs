@@ -0,0 +1,214 @@
+use glam::{BVec3, Vec3};
+
+use crate::{mesh::Voxel, util::WorldVoxelPos, world::VoxelWorld};
+
+use super::Aabb;
+
+// Public struct `Sweep` describing the result of moving a box through the voxel world.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Sweep {
+    // The box after moving.
+    pub aabb: Aabb,
+    // The motion that was actually applied.
+    pub motion: Vec3,
+    // The axes along which the box was stopped by a voxel.
+    pub collided: BVec3,
+}
+
+impl Sweep {
+    // Public method `grounded` checks if the box landed on a voxel while moving down.
+    pub fn grounded(&self, motion: Vec3) -> bool {
+        self.collided.y && motion.y < 0.0
+    }
+}
+
+impl VoxelWorld {
+    // Public method `is_solid` checks if the voxel at a world voxel position blocks movement.
+    // Voxels of chunks that aren't generated don't block movement.
+    pub fn is_solid(&self, position: WorldVoxelPos) -> bool {
+        matches!(
+            self.get(position),
+            Some(Voxel::Opaque(_) | Voxel::Transparent(_))
+        )
+    }
+
+    /// Public method `sweep` moves a box by `motion`, resolving collisions against solid voxels
+    /// one axis at a time, vertical first so the box settles on the ground before sliding along it.
+    /// Boxes that start out inside voxels aren't pushed out, but can move away from them.
+    pub fn sweep(&self, aabb: Aabb, motion: Vec3) -> Sweep {
+        let solids: Vec<Aabb> = aabb
+            .expand(motion)
+            .voxels(&self.config)
+            .filter(|position| self.is_solid(*position))
+            .map(|position| Aabb::voxel(position, &self.config))
+            .filter(|voxel| !voxel.intersects(&aabb))
+            .collect();
+
+        let mut aabb = aabb;
+        let mut applied = Vec3::ZERO;
+        for axis in [1, 0, 2] {
+            let mut distance = motion[axis];
+            for voxel in solids.iter() {
+                distance = aabb.clip(voxel, axis, distance);
+            }
+
+            let mut offset = Vec3::ZERO;
+            offset[axis] = distance;
+            aabb = aabb.translate(offset);
+            applied[axis] = distance;
+        }
+
+        Sweep {
+            aabb,
+            motion: applied,
+            collided: applied.cmpne(motion),
+        }
+    }
+
+    /// Public method `sweep_step` moves a box like `sweep`, but lets it climb ledges up to `step_height`
+    /// when its horizontal motion is blocked. The step is only taken if it gets the box further.
+    pub fn sweep_step(&self, aabb: Aabb, motion: Vec3, step_height: f32) -> Sweep {
+        let sweep = self.sweep(aabb, motion);
+        if step_height <= 0.0 || !(sweep.collided.x || sweep.collided.z) {
+            return sweep;
+        }
+
+        // Lift the box, move it horizontally and put it back down.
+        let up = self.sweep(aabb, Vec3::new(0.0, step_height, 0.0));
+        let across = self.sweep(up.aabb, Vec3::new(motion.x, 0.0, motion.z));
+        let down = self.sweep(
+            across.aabb,
+            Vec3::new(0.0, motion.y.min(0.0) - up.motion.y, 0.0),
+        );
+
+        let horizontal = |motion: Vec3| motion.x * motion.x + motion.z * motion.z;
+        let stepped = up.motion + across.motion + down.motion;
+        if horizontal(stepped) <= horizontal(sweep.motion) {
+            return sweep;
+        }
+
+        Sweep {
+            aabb: down.aabb,
+            motion: stepped,
+            collided: BVec3::new(across.collided.x, down.collided.y, across.collided.z),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block::STONE, light::Emission, mesh::ChunkMesh, util::ChunkPos, world::WorldConfig,
+    };
+
+    // The sizes of the box moved around, in voxels like the player.
+    const WIDTH: f32 = 0.6;
+    const HEIGHT: f32 = 1.8;
+    const STEP_HEIGHT: f32 = 1.05;
+
+    // Function `world` generates a single chunk with a floor at y = 0 and stone at the given positions.
+    fn world(stone: &[[i32; 3]]) -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        world.insert(chunk, &Emission::default());
+
+        for x in 0..8 {
+            for z in 0..8 {
+                world.set(WorldVoxelPos::new(x, 0, z), STONE);
+            }
+        }
+        for position in stone {
+            world.set(WorldVoxelPos::from(*position), STONE);
+        }
+        world
+    }
+
+    // Function `player` returns the box of a player standing with its feet centered at `x`, `y`, `z`.
+    fn player(x: f32, y: f32, z: f32) -> Aabb {
+        let half = WIDTH / 2.0;
+        Aabb::new(
+            Vec3::new(x - half, y, z - half),
+            Vec3::new(x + half, y + HEIGHT, z + half),
+        )
+    }
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn falling_lands_on_the_ground() {
+        let world = world(&[]);
+        let motion = Vec3::new(0.0, -5.0, 0.0);
+        let sweep = world.sweep(player(3.0, 2.5, 3.0), motion);
+
+        // The top of the floor voxels is at y = 0.5.
+        assert_near(sweep.aabb.min.y, 0.5);
+        assert_near(sweep.motion.y, -2.0);
+        assert_eq!(sweep.collided, BVec3::new(false, true, false));
+        assert!(sweep.grounded(motion));
+    }
+
+    #[test]
+    fn hitting_a_ceiling_isnt_grounded() {
+        let world = world(&[[3, 4, 3]]);
+        let motion = Vec3::new(0.0, 2.0, 0.0);
+        let sweep = world.sweep(player(3.0, 0.5, 3.0), motion);
+
+        assert_near(sweep.aabb.max.y, 3.5);
+        assert!(sweep.collided.y);
+        assert!(!sweep.grounded(motion));
+
+        // Jumping without hitting anything isn't grounded either.
+        let sweep = world.sweep(player(5.0, 0.5, 5.0), motion);
+        assert_eq!(sweep.motion, motion);
+        assert!(!sweep.grounded(motion));
+    }
+
+    #[test]
+    fn walls_clip_only_their_axis() {
+        let wall: Vec<_> = (0..8).map(|z| [5, 1, z]).collect();
+        let world = world(&wall);
+        let motion = Vec3::new(3.0, -0.1, 2.0);
+        let sweep = world.sweep(player(3.0, 0.5, 2.0), motion);
+
+        // The box stops against the wall, but keeps sliding along it.
+        assert_near(sweep.aabb.max.x, 4.5);
+        assert_near(sweep.motion.z, 2.0);
+        assert_eq!(sweep.collided, BVec3::new(true, true, false));
+        assert!(sweep.grounded(motion));
+    }
+
+    #[test]
+    fn steps_climb_one_voxel_ledges() {
+        let ledge: Vec<_> = (0..8).map(|z| [5, 1, z]).collect();
+        let world = world(&ledge);
+        let motion = Vec3::new(2.0, -0.1, 0.0);
+        let sweep = world.sweep_step(player(3.5, 0.5, 3.0), motion, STEP_HEIGHT);
+
+        assert_near(sweep.aabb.min.x, 5.2);
+        assert_near(sweep.aabb.min.y, 1.5);
+        assert_eq!(sweep.collided, BVec3::new(false, true, false));
+        assert!(sweep.grounded(motion));
+
+        // Without a step height the ledge is a wall.
+        let sweep = world.sweep_step(player(3.5, 0.5, 3.0), motion, 0.0);
+        assert_near(sweep.aabb.max.x, 4.5);
+        assert!(sweep.collided.x);
+    }
+
+    #[test]
+    fn steps_dont_climb_two_voxel_walls() {
+        let wall: Vec<_> = (0..8).flat_map(|z| [[5, 1, z], [5, 2, z]]).collect();
+        let world = world(&wall);
+        let motion = Vec3::new(2.0, -0.1, 0.0);
+        let sweep = world.sweep_step(player(3.5, 0.5, 3.0), motion, STEP_HEIGHT);
+
+        assert_near(sweep.aabb.max.x, 4.5);
+        assert_near(sweep.aabb.min.y, 0.5);
+        assert_eq!(sweep.collided, BVec3::new(true, true, false));
+    }
+}
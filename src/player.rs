@@ -0,0 +1,30 @@
+pub mod controller;
+pub mod cursor;
+pub mod settings;
+
+use bevy::{
+    app::{App, Plugin, PostUpdate, Startup, Update},
+    ecs::schedule::IntoSystemConfigs,
+};
+pub use controller::{look_handler, movement_handler, setup_player, Player, PlayerCamera};
+pub use cursor::cursor_grab_handler;
+pub use settings::{PlayerSettings, SPAWN_POSITION};
+
+use crate::physics::physics_handler;
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerSettings>()
+            .add_systems(Startup, setup_player)
+            .add_systems(
+                Update,
+                (look_handler, movement_handler)
+                    .chain()
+                    .before(physics_handler),
+            )
+            // Grab the cursor after the update, so the click grabbing it doesn't also break a block.
+            .add_systems(PostUpdate, cursor_grab_handler);
+    }
+}
@@ -0,0 +1,201 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    core_pipeline::core_3d::Camera3dBundle,
+    ecs::{
+        component::Component,
+        event::EventReader,
+        query::{With, Without},
+        system::{Commands, Query, Res},
+    },
+    hierarchy::{BuildChildren, Children},
+    input::{keyboard::KeyCode, mouse::MouseMotion, Input},
+    math::{Quat, Vec2, Vec3},
+    render::prelude::SpatialBundle,
+    transform::components::Transform,
+    window::{PrimaryWindow, Window},
+};
+
+use crate::{
+    physics::Body,
+    settings::VoxelSettings,
+    world::{ChunkLoader, VoxelWorld},
+};
+
+use super::{cursor::is_grabbed, PlayerSettings, SPAWN_POSITION};
+
+// Public struct `Player` marks the entity walking around the world, its translation is at its feet.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Player {
+    // Whether the player flies through voxels instead of walking.
+    pub fly: bool,
+    pub crouching: bool,
+    // The rotation of the player around the vertical axis and of the camera around the horizontal axis, in radians.
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+// Public struct `PlayerCamera` marks the camera child of the player, placed at its eyes.
+#[derive(Component)]
+pub struct PlayerCamera;
+
+// This system spawns the player with its camera, the chunks are loaded around the player.
+pub fn setup_player(
+    mut commands: Commands,
+    settings: Res<PlayerSettings>,
+    voxel_settings: Res<VoxelSettings>,
+    world: Res<VoxelWorld>,
+) {
+    let voxel_size = world.config.voxel_size;
+    let settings = settings.scaled(voxel_size);
+    let half_width = settings.width / 2.0;
+    let mut body = Body::new(
+        Vec3::new(-half_width, 0.0, -half_width),
+        Vec3::new(half_width, settings.height, half_width),
+    );
+    body.step_height = settings.step_height;
+
+    commands
+        .spawn((
+            Player::default(),
+            body,
+            ChunkLoader {
+                distance: voxel_settings.render_distance,
+            },
+            SpatialBundle::from_transform(Transform::from_translation(SPAWN_POSITION * voxel_size)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Camera3dBundle {
+                    transform: Transform::from_xyz(0.0, settings.eye_height, 0.0),
+                    ..Default::default()
+                },
+                PlayerCamera,
+            ));
+        });
+}
+
+// This system turns the player and tilts its camera with the mouse while the cursor is grabbed.
+pub fn look_handler(
+    mut motion: EventReader<MouseMotion>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    settings: Res<PlayerSettings>,
+    mut players: Query<(&mut Player, &mut Transform, &Children)>,
+    mut cameras: Query<&mut Transform, (With<PlayerCamera>, Without<Player>)>,
+) {
+    let grabbed = windows.get_single().map(is_grabbed).unwrap_or(false);
+    let delta = motion
+        .read()
+        .fold(Vec2::ZERO, |sum, event| sum + event.delta);
+    if !grabbed || delta == Vec2::ZERO {
+        return;
+    }
+
+    for (mut player, mut transform, children) in players.iter_mut() {
+        player.yaw -= delta.x * settings.sensitivity;
+        // Stop just short of looking straight up or down, so the view doesn't flip.
+        player.pitch = (player.pitch - delta.y * settings.sensitivity)
+            .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+
+        transform.rotation = Quat::from_rotation_y(player.yaw);
+        for child in children.iter() {
+            if let Ok(mut camera) = cameras.get_mut(*child) {
+                camera.rotation = Quat::from_rotation_x(player.pitch);
+            }
+        }
+    }
+}
+
+/// This system moves the player with the keyboard by setting the velocity of its body.
+/// WASD walks, shift sprints, space jumps and control crouches, F toggles flying,
+/// in which space and control move up and down instead.
+pub fn movement_handler(
+    keys: Res<Input<KeyCode>>,
+    settings: Res<PlayerSettings>,
+    world: Res<VoxelWorld>,
+    mut players: Query<(&mut Player, &mut Body, &Transform, &Children)>,
+    mut cameras: Query<&mut Transform, (With<PlayerCamera>, Without<Player>)>,
+) {
+    let settings = settings.scaled(world.config.voxel_size);
+    for (mut player, mut body, transform, children) in players.iter_mut() {
+        if keys.just_pressed(KeyCode::F) {
+            player.fly = !player.fly;
+            body.noclip = player.fly;
+            body.gravity = !player.fly;
+            body.velocity = Vec3::ZERO;
+        }
+
+        // The horizontal direction to move in, relative to where the player is facing.
+        let mut direction = Vec3::ZERO;
+        for (key, offset) in [
+            (KeyCode::W, transform.forward()),
+            (KeyCode::S, transform.back()),
+            (KeyCode::A, transform.left()),
+            (KeyCode::D, transform.right()),
+        ] {
+            if keys.pressed(key) {
+                direction += offset;
+            }
+        }
+        let direction = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero();
+        let sprinting = keys.pressed(KeyCode::ShiftLeft);
+
+        if player.fly {
+            let speed = if sprinting {
+                settings.fly_sprint_speed
+            } else {
+                settings.fly_speed
+            };
+            let mut velocity = direction;
+            if keys.pressed(KeyCode::Space) {
+                velocity.y += 1.0;
+            }
+            if keys.pressed(KeyCode::ControlLeft) {
+                velocity.y -= 1.0;
+            }
+            body.velocity = velocity * speed;
+            continue;
+        }
+
+        // Crouch while control is held, only standing up again when there is room above the player.
+        let crouch = keys.pressed(KeyCode::ControlLeft);
+        if crouch != player.crouching {
+            let growth = settings.height - settings.crouch_height;
+            let blocked = !crouch
+                && world
+                    .sweep(body.aabb(transform.translation), Vec3::Y * growth)
+                    .collided
+                    .y;
+
+            if !blocked {
+                player.crouching = crouch;
+                let (height, eye_height) = if crouch {
+                    (settings.crouch_height, settings.crouch_eye_height)
+                } else {
+                    (settings.height, settings.eye_height)
+                };
+
+                body.max.y = height;
+                for child in children.iter() {
+                    if let Ok(mut camera) = cameras.get_mut(*child) {
+                        camera.translation.y = eye_height;
+                    }
+                }
+            }
+        }
+
+        let speed = if player.crouching {
+            settings.crouch_speed
+        } else if sprinting {
+            settings.sprint_speed
+        } else {
+            settings.walk_speed
+        };
+        body.velocity.x = direction.x * speed;
+        body.velocity.z = direction.z * speed;
+
+        if keys.pressed(KeyCode::Space) && body.grounded {
+            body.velocity.y = settings.jump_speed;
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use bevy::{
+    ecs::{
+        query::With,
+        system::{Query, Res},
+    },
+    input::{keyboard::KeyCode, mouse::MouseButton, Input},
+    window::{CursorGrabMode, PrimaryWindow, Window},
+};
+
+// This system grabs and hides the cursor on click and releases it on escape.
+pub fn cursor_grab_handler(
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        window.cursor.grab_mode = CursorGrabMode::Locked;
+        window.cursor.visible = false;
+    }
+
+    if keys.just_pressed(KeyCode::Escape) {
+        window.cursor.grab_mode = CursorGrabMode::None;
+        window.cursor.visible = true;
+    }
+}
+
+// Function `is_grabbed` checks if the cursor is grabbed by the window.
+pub fn is_grabbed(window: &Window) -> bool {
+    window.cursor.grab_mode != CursorGrabMode::None
+}
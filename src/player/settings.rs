@@ -0,0 +1,71 @@
+use bevy::{ecs::system::Resource, math::Vec3};
+
+/// The position the feet of the player start at, in voxels.
+pub const SPAWN_POSITION: Vec3 = Vec3::new(0.0, 25.0, 0.0);
+
+// Public struct `PlayerSettings` holding the dimensions and speeds of the player, in voxels
+// so the player keeps its size whatever the voxel size of the world, see `scaled`.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct PlayerSettings {
+    // The height of the camera above the feet of the player, standing and crouching.
+    pub eye_height: f32,
+    pub crouch_eye_height: f32,
+    // The height of the player's bounding box, standing and crouching.
+    pub height: f32,
+    pub crouch_height: f32,
+    // The width of the player's bounding box.
+    pub width: f32,
+    // The height of the ledges the player walks up without jumping.
+    pub step_height: f32,
+    // The horizontal speeds of the player, in voxels per second.
+    pub walk_speed: f32,
+    pub sprint_speed: f32,
+    pub crouch_speed: f32,
+    pub fly_speed: f32,
+    pub fly_sprint_speed: f32,
+    // The upwards speed of a jump, in voxels per second.
+    pub jump_speed: f32,
+    // The rotation of the camera per pixel of mouse movement, in radians.
+    pub sensitivity: f32,
+}
+
+impl Default for PlayerSettings {
+    fn default() -> Self {
+        Self {
+            eye_height: 1.6,
+            crouch_eye_height: 1.3,
+            height: 1.8,
+            crouch_height: 1.5,
+            width: 0.6,
+            step_height: 1.05,
+            walk_speed: 4.5,
+            sprint_speed: 6.0,
+            crouch_speed: 1.5,
+            fly_speed: 10.0,
+            fly_sprint_speed: 25.0,
+            jump_speed: 9.0,
+            sensitivity: 0.002,
+        }
+    }
+}
+
+impl PlayerSettings {
+    // Public method `scaled` converts the dimensions and speeds from voxels to world units.
+    pub fn scaled(&self, voxel_size: f32) -> Self {
+        Self {
+            eye_height: self.eye_height * voxel_size,
+            crouch_eye_height: self.crouch_eye_height * voxel_size,
+            height: self.height * voxel_size,
+            crouch_height: self.crouch_height * voxel_size,
+            width: self.width * voxel_size,
+            step_height: self.step_height * voxel_size,
+            walk_speed: self.walk_speed * voxel_size,
+            sprint_speed: self.sprint_speed * voxel_size,
+            crouch_speed: self.crouch_speed * voxel_size,
+            fly_speed: self.fly_speed * voxel_size,
+            fly_sprint_speed: self.fly_sprint_speed * voxel_size,
+            jump_speed: self.jump_speed * voxel_size,
+            sensitivity: self.sensitivity,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod file;
+pub mod region;
+pub mod vox;
+
+pub use file::{
+    read_varint, read_voxel, write_varint, write_voxel, MAX_SCHEMATIC_VOLUME, SCHEMATIC_EXTENSION,
+};
+pub use region::Schematic;
+pub use vox::{VoxFile, VoxModel, MAX_VOX_SIZE, VOX_EXTENSION};
@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{mesh::Voxel, util::Color};
+
+use super::Schematic;
+
+/// The file extension of saved schematics.
+pub const SCHEMATIC_EXTENSION: &str = "vxs";
+
+/// The most voxels a schematic file may hold, 256 along each axis.
+/// Sizes are read before the voxels, so larger files are rejected before anything is allocated.
+pub const MAX_SCHEMATIC_VOLUME: usize = 1 << 24;
+
+// The bytes every schematic file starts with, followed by the version of the format.
+const MAGIC: &[u8; 4] = b"VXSC";
+const VERSION: u8 = 1;
+
+// Function `invalid` creates the error returned for malformed files.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Public function `write_varint` writes an unsigned integer using 7 bits per byte, small numbers taking a single byte.
+pub fn write_varint(writer: &mut impl Write, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+// Public function `read_varint` reads an unsigned integer written by `write_varint`.
+pub fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid("varint too long"))
+}
+
+// Public function `write_voxel` writes a voxel as a kind byte followed by its color and liquid level, if any.
+pub fn write_voxel(writer: &mut impl Write, voxel: Voxel) -> io::Result<()> {
+    let (kind, color, level) = match voxel {
+        Voxel::Empty => return writer.write_all(&[0]),
+        Voxel::Opaque(color) => (1, color, None),
+        Voxel::Transparent(color) => (2, color, None),
+        Voxel::Liquid(color, level) => (3, color, Some(level)),
+    };
+
+    writer.write_all(&[kind, color.red, color.green, color.blue, color.alpha])?;
+    if let Some(level) = level {
+        writer.write_all(&[level])?;
+    }
+    Ok(())
+}
+
+// Public function `read_voxel` reads a voxel written by `write_voxel`.
+pub fn read_voxel(reader: &mut impl Read) -> io::Result<Voxel> {
+    let mut kind = [0];
+    reader.read_exact(&mut kind)?;
+    if kind[0] == 0 {
+        return Ok(Voxel::Empty);
+    }
+
+    let mut rgba = [0; 4];
+    reader.read_exact(&mut rgba)?;
+    let color = Color {
+        red: rgba[0],
+        green: rgba[1],
+        blue: rgba[2],
+        alpha: rgba[3],
+    };
+
+    match kind[0] {
+        1 => Ok(Voxel::Opaque(color)),
+        2 => Ok(Voxel::Transparent(color)),
+        3 => {
+            let mut level = [0];
+            reader.read_exact(&mut level)?;
+            Ok(Voxel::Liquid(color, level[0]))
+        }
+        _ => Err(invalid("unknown voxel kind")),
+    }
+}
+
+impl Schematic {
+    /// Public method `write` writes the schematic in a compact format: its size, a palette of the distinct voxels
+    /// it contains, and the voxels as runs of palette indices, so large areas of air or stone take a few bytes.
+    /// Fails if the schematic holds more than `MAX_SCHEMATIC_VOLUME` voxels, as it couldn't be read back.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        if self.voxels.len() > MAX_SCHEMATIC_VOLUME {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "schematic too large",
+            ));
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        for size in self.size {
+            write_varint(writer, size as u64)?;
+        }
+
+        let mut palette = Vec::new();
+        let mut indices = HashMap::new();
+        for voxel in self.voxels.iter() {
+            indices.entry(*voxel).or_insert_with(|| {
+                palette.push(*voxel);
+                palette.len() - 1
+            });
+        }
+        write_varint(writer, palette.len() as u64)?;
+        for voxel in palette {
+            write_voxel(writer, voxel)?;
+        }
+
+        let mut voxels = self.voxels.iter().peekable();
+        while let Some(voxel) = voxels.next() {
+            let mut run = 1;
+            while voxels.next_if_eq(&voxel).is_some() {
+                run += 1;
+            }
+            write_varint(writer, run)?;
+            write_varint(writer, indices[voxel] as u64)?;
+        }
+
+        Ok(())
+    }
+
+    // Public method `read` reads a schematic written by `write`.
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a schematic"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid("unsupported schematic version"));
+        }
+
+        let mut size = [0; 3];
+        for size in size.iter_mut() {
+            *size = read_varint(reader)? as usize;
+        }
+        let volume = size
+            .iter()
+            .try_fold(1usize, |volume, size| volume.checked_mul(*size))
+            .filter(|volume| *volume <= MAX_SCHEMATIC_VOLUME)
+            .ok_or_else(|| invalid("schematic too large"))?;
+
+        // Every voxel of the palette is distinct, so there can't be more of them than voxels.
+        let palette_size = read_varint(reader)?;
+        if palette_size > volume as u64 {
+            return Err(invalid("palette larger than the schematic"));
+        }
+        let palette = (0..palette_size)
+            .map(|_| read_voxel(reader))
+            .collect::<io::Result<Vec<Voxel>>>()?;
+
+        // The voxels grow as runs are read, so a truncated file doesn't allocate the whole volume.
+        let mut voxels = Vec::new();
+        while voxels.len() < volume {
+            let run = read_varint(reader)? as usize;
+            let voxel = palette
+                .get(read_varint(reader)? as usize)
+                .ok_or_else(|| invalid("palette index out of range"))?;
+            if run > volume - voxels.len() {
+                return Err(invalid("run exceeds schematic size"));
+            }
+            voxels.resize(voxels.len() + run, *voxel);
+        }
+
+        Ok(Self { size, voxels })
+    }
+
+    // Public method `save` writes the schematic to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    // Public method `load` reads a schematic from a file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function `bytes` writes a schematic into memory.
+    fn bytes(schematic: &Schematic) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        schematic.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn schematic_round_trips() {
+        let stone = Voxel::Opaque(Color::new(120, 120, 120));
+        let glass = Voxel::Transparent(Color {
+            red: 200,
+            green: 220,
+            blue: 255,
+            alpha: 100,
+        });
+        let water = Voxel::Liquid(Color::new(30, 60, 200), 5);
+
+        let mut schematic = Schematic::new([5, 3, 4]);
+        for (i, voxel) in schematic.voxels.iter_mut().enumerate() {
+            *voxel = match i % 7 {
+                0..=2 => stone,
+                3 => glass,
+                4 => water,
+                _ => Voxel::Empty,
+            };
+        }
+
+        let read = Schematic::read(&mut bytes(&schematic).as_slice()).unwrap();
+        assert_eq!(read, schematic);
+    }
+
+    #[test]
+    fn uniform_schematics_take_a_few_bytes() {
+        let schematic = Schematic::new([64, 64, 64]);
+        let bytes = bytes(&schematic);
+        assert!(bytes.len() < 16, "{}", bytes.len());
+        assert_eq!(Schematic::read(&mut bytes.as_slice()).unwrap(), schematic);
+    }
+
+    #[test]
+    fn varints_round_trip() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+            assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), value);
+        }
+    }
+
+    // Function `header` writes the header of a schematic file with the given size and palette size.
+    fn header(size: [u64; 3], palette: u64) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        for size in size {
+            write_varint(&mut bytes, size).unwrap();
+        }
+        write_varint(&mut bytes, palette).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn oversized_files_are_rejected() {
+        for (size, palette) in [
+            ([1 << 20, 1 << 20, 1 << 20], 1),
+            ([u64::MAX, u64::MAX, 2], 1),
+            ([257, 256, 256], 1),
+            ([2, 2, 2], u64::MAX),
+        ] {
+            let error = Schematic::read(&mut header(size, palette).as_slice()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{size:?}");
+        }
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        // The largest allowed size with a single voxel of palette, but no runs.
+        let mut file = header([256, 256, 256], 1);
+        write_voxel(&mut file, Voxel::Empty).unwrap();
+        let error = Schematic::read(&mut file.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        let written = bytes(&Schematic::new([3, 3, 3]));
+        let error = Schematic::read(&mut &written[..written.len() - 1]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn runs_past_the_end_are_rejected() {
+        let mut bytes = header([2, 2, 2], 1);
+        write_voxel(&mut bytes, Voxel::Empty).unwrap();
+        write_varint(&mut bytes, 9).unwrap();
+        write_varint(&mut bytes, 0).unwrap();
+        let error = Schematic::read(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
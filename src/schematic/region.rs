@@ -0,0 +1,260 @@
+use crate::{
+    mesh::{Axis, Voxel},
+    util::WorldVoxelPos,
+    world::{EditRecord, VoxelChange, VoxelWorld},
+};
+
+// Public struct `Schematic` holding a box of voxels copied out of the world, independent of where it came from.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Schematic {
+    // The number of voxels along each axis.
+    pub size: [usize; 3],
+    // The voxels of the box, ordered x first, then y, then z.
+    pub voxels: Vec<Voxel>,
+}
+
+impl Schematic {
+    // Public method `new` creates an empty schematic of the given size.
+    pub fn new(size: [usize; 3]) -> Self {
+        Self {
+            size,
+            voxels: vec![Voxel::Empty; size[0] * size[1] * size[2]],
+        }
+    }
+
+    /// Public method `copy` copies the box between two world voxel positions, both included and in any order.
+    /// Voxels of chunks that aren't generated are copied as empty.
+    pub fn copy(world: &VoxelWorld, a: WorldVoxelPos, b: WorldVoxelPos) -> Self {
+        let min = a.min(b);
+        let size = [
+            a.x.abs_diff(b.x) as usize + 1,
+            a.y.abs_diff(b.y) as usize + 1,
+            a.z.abs_diff(b.z) as usize + 1,
+        ];
+
+        let mut schematic = Self::new(size);
+        for i in 0..schematic.voxels.len() {
+            let [x, y, z] = schematic.delinearize(i);
+            let position = min.offset([x as i32, y as i32, z as i32]);
+            schematic.voxels[i] = world.get(position).unwrap_or_default();
+        }
+
+        schematic
+    }
+
+    pub fn linearize(&self, [x, y, z]: [usize; 3]) -> usize {
+        x + self.size[0] * (y + self.size[1] * z)
+    }
+
+    pub fn delinearize(&self, index: usize) -> [usize; 3] {
+        let [x_size, y_size, _] = self.size;
+        [
+            index % x_size,
+            (index / x_size) % y_size,
+            index / (x_size * y_size),
+        ]
+    }
+
+    pub fn get(&self, position: [usize; 3]) -> Voxel {
+        self.voxels[self.linearize(position)]
+    }
+
+    // Public method `rotate` returns the schematic turned a quarter turn around the Y axis,
+    // so the X axis of the schematic becomes its negative Z axis. The X and Z sizes swap.
+    pub fn rotate(&self) -> Self {
+        let [x_size, y_size, z_size] = self.size;
+        let mut rotated = Self::new([z_size, y_size, x_size]);
+        for (i, voxel) in self.voxels.iter().enumerate() {
+            let [x, y, z] = self.delinearize(i);
+            let index = rotated.linearize([z, y, x_size - 1 - x]);
+            rotated.voxels[index] = *voxel;
+        }
+
+        rotated
+    }
+
+    // Public method `mirror` returns the schematic flipped along an axis,
+    // `Axis::X` and `Axis::Z` mirroring it across a vertical plane.
+    pub fn mirror(&self, axis: Axis) -> Self {
+        let axis = match axis {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        };
+        let mut mirrored = Self::new(self.size);
+        for (i, voxel) in self.voxels.iter().enumerate() {
+            let mut position = self.delinearize(i);
+            position[axis] = self.size[axis] - 1 - position[axis];
+            let index = mirrored.linearize(position);
+            mirrored.voxels[index] = *voxel;
+        }
+
+        mirrored
+    }
+
+    /// Public method `paste` writes the schematic into the world with its minimum corner at `origin`,
+    /// and returns a record of the voxels that changed. With `skip_air`, empty voxels of the schematic
+    /// leave the world untouched. Voxels of chunks that aren't generated are skipped.
+    /// The changed voxels are relit and notified together, like brushes.
+    pub fn paste(
+        &self,
+        world: &mut VoxelWorld,
+        origin: WorldVoxelPos,
+        skip_air: bool,
+    ) -> EditRecord {
+        let record = self.paste_quietly(world, origin, skip_air);
+        world.notify(&record);
+        record
+    }
+
+    // Public method `paste_quietly` pastes the schematic like `paste`, without relighting or notifying the changed voxels,
+    // so edits pasting several schematics can do it once for all of them.
+    pub fn paste_quietly(
+        &self,
+        world: &mut VoxelWorld,
+        origin: WorldVoxelPos,
+        skip_air: bool,
+    ) -> EditRecord {
+        let mut record = EditRecord::default();
+
+        for (i, voxel) in self.voxels.iter().enumerate() {
+            if skip_air && *voxel == Voxel::Empty {
+                continue;
+            }
+
+            let [x, y, z] = self.delinearize(i);
+            let position = origin.offset([x as i32, y as i32, z as i32]);
+            match world.set_quietly(position, *voxel) {
+                Some(before) if before != *voxel => record.changes.push(VoxelChange {
+                    position,
+                    before,
+                    after: *voxel,
+                }),
+                _ => {}
+            }
+        }
+
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        light::Emission,
+        mesh::ChunkMesh,
+        util::{ChunkPos, Color},
+        world::WorldConfig,
+    };
+
+    const RED: Voxel = Voxel::Opaque(Color::new(255, 0, 0));
+    const GREEN: Voxel = Voxel::Opaque(Color::new(0, 255, 0));
+    const BLUE: Voxel = Voxel::Opaque(Color::new(0, 0, 255));
+
+    // Function `world` generates a single empty chunk at the origin.
+    fn world() -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        world.insert(chunk, &Emission::default());
+        world
+    }
+
+    // Function `line` returns a schematic of three voxels along X: red, empty, blue.
+    fn line() -> Schematic {
+        Schematic {
+            size: [3, 1, 1],
+            voxels: vec![RED, Voxel::Empty, BLUE],
+        }
+    }
+
+    #[test]
+    fn copy_takes_the_box_in_any_order() {
+        let mut world = world();
+        world.set(WorldVoxelPos::new(1, 2, 3), RED);
+        world.set(WorldVoxelPos::new(2, 3, 3), GREEN);
+
+        let schematic = Schematic::copy(
+            &world,
+            WorldVoxelPos::new(2, 3, 3),
+            WorldVoxelPos::new(1, 2, 3),
+        );
+        assert_eq!(schematic.size, [2, 2, 1]);
+        assert_eq!(schematic.voxels, [RED, Voxel::Empty, Voxel::Empty, GREEN]);
+
+        // Voxels of chunks that aren't generated are copied as empty.
+        let outside = Schematic::copy(
+            &world,
+            WorldVoxelPos::new(-1, 0, 0),
+            WorldVoxelPos::new(-1, 0, 0),
+        );
+        assert_eq!(outside.voxels, [Voxel::Empty]);
+    }
+
+    #[test]
+    fn rotate_turns_x_into_negative_z() {
+        let rotated = line().rotate();
+        assert_eq!(rotated.size, [1, 1, 3]);
+        assert_eq!(rotated.voxels, [BLUE, Voxel::Empty, RED]);
+
+        // Four quarter turns give the schematic back.
+        let mut schematic = Schematic::new([2, 2, 3]);
+        schematic.voxels[1] = GREEN;
+        let turned = schematic.rotate().rotate().rotate().rotate();
+        assert_eq!(turned, schematic);
+        assert_ne!(schematic.rotate(), schematic);
+    }
+
+    #[test]
+    fn mirror_flips_one_axis() {
+        assert_eq!(line().mirror(Axis::X).voxels, [BLUE, Voxel::Empty, RED]);
+        assert_eq!(line().mirror(Axis::Z), line());
+
+        let rotated = line().rotate();
+        assert_eq!(rotated.mirror(Axis::Z).voxels, line().voxels);
+        assert_eq!(rotated.mirror(Axis::Z).mirror(Axis::Z), rotated);
+    }
+
+    #[test]
+    fn paste_skips_air_when_asked() {
+        let mut world = world();
+        let origin = WorldVoxelPos::new(2, 2, 2);
+        world.set(origin.offset([1, 0, 0]), GREEN);
+        world.relight.clear();
+        world.changed.clear();
+
+        let record = line().paste(&mut world, origin, true);
+        assert_eq!(record.changes.len(), 2);
+        assert_eq!(world.get(origin.offset([1, 0, 0])), Some(GREEN));
+        assert_eq!(world.relight.len(), 2);
+
+        // Pasting air clears the voxel in the middle, the others didn't change.
+        let record = line().paste(&mut world, origin, false);
+        assert_eq!(
+            record.changes,
+            [VoxelChange {
+                position: origin.offset([1, 0, 0]),
+                before: GREEN,
+                after: Voxel::Empty,
+            }]
+        );
+        assert_eq!(world.get(origin), Some(RED));
+        assert_eq!(world.get(origin.offset([2, 0, 0])), Some(BLUE));
+    }
+
+    #[test]
+    fn paste_only_notifies_the_boundary() {
+        let mut world = world();
+        let schematic = Schematic {
+            size: [3, 3, 3],
+            voxels: vec![RED; 27],
+        };
+        let record = schematic.paste(&mut world, WorldVoxelPos::new(1, 1, 1), false);
+        assert_eq!(record.changes.len(), 27);
+        assert_eq!(world.relight.len(), 27);
+        assert_eq!(world.changed.len(), 26);
+        assert!(!world.changed.contains(&WorldVoxelPos::new(2, 2, 2)));
+    }
+}
@@ -0,0 +1,757 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    mesh::Voxel,
+    util::{Color, WorldVoxelPos},
+    world::{EditRecord, VoxelWorld},
+};
+
+use super::Schematic;
+
+/// The file extension of MagicaVoxel files.
+pub const VOX_EXTENSION: &str = "vox";
+
+/// The largest size of a MagicaVoxel model along any axis.
+pub const MAX_VOX_SIZE: usize = 256;
+
+// The version written into exported files.
+const VERSION: i32 = 150;
+
+// Public struct `VoxModel` holding a single model of a MagicaVoxel file, in MagicaVoxel's Z up coordinates.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VoxModel {
+    pub size: [u32; 3],
+    // The position and palette index of every voxel of the model.
+    pub voxels: Vec<([u8; 3], u8)>,
+    // The position of the center of the model in the scene, rounded down like MagicaVoxel does.
+    pub translation: [i32; 3],
+}
+
+impl VoxModel {
+    // Public method `min` returns the position of the minimum corner of the model in the scene,
+    // failing if it lies outside the range of an `i32`.
+    pub fn min(&self) -> io::Result<[i32; 3]> {
+        let mut min = self.translation;
+        for (axis, size) in min.iter_mut().zip(self.size) {
+            *axis = axis
+                .checked_sub((size / 2) as i32)
+                .ok_or_else(|| invalid("model translation out of range"))?;
+        }
+        Ok(min)
+    }
+
+    // Public method `to_schematic` converts the model to a schematic, turning MagicaVoxel's Z axis into our Y axis.
+    // Palette colors that aren't fully opaque become transparent voxels.
+    pub fn to_schematic(&self, palette: &[Color; 256]) -> Schematic {
+        let [sx, sy, sz] = self.size.map(|size| size as usize);
+        let mut schematic = Schematic::new([sx, sz, sy]);
+
+        for ([x, y, z], index) in self.voxels.iter() {
+            let [x, y, z] = [*x as usize, *y as usize, *z as usize];
+            if x >= sx || y >= sy || z >= sz || *index == 0 {
+                continue;
+            }
+
+            let color = palette[*index as usize];
+            let voxel = if color.alpha == 255 {
+                Voxel::Opaque(color)
+            } else {
+                Voxel::Transparent(color)
+            };
+            let i = schematic.linearize([x, z, sy - 1 - y]);
+            schematic.voxels[i] = voxel;
+        }
+
+        schematic
+    }
+
+    // Public method `origin` returns the world voxel position the minimum corner of the model maps to,
+    // failing if it lies outside the range of an `i32`.
+    pub fn origin(&self) -> io::Result<WorldVoxelPos> {
+        let [x, y, z] = self.min()?;
+        // The maximum corner along MagicaVoxel's Y axis becomes our minimum along Z.
+        let max = y
+            .checked_add(self.size[1] as i32 - 1)
+            .and_then(i32::checked_neg)
+            .ok_or_else(|| invalid("model translation out of range"))?;
+        Ok(WorldVoxelPos { x, y: z, z: max })
+    }
+}
+
+// Public struct `VoxFile` holding the models of a MagicaVoxel file and the palette they share.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VoxFile {
+    pub models: Vec<VoxModel>,
+    // The colors of the palette, voxels refer to them by index, index 0 being unused.
+    pub palette: [Color; 256],
+}
+
+impl Default for VoxFile {
+    fn default() -> Self {
+        Self {
+            models: Vec::new(),
+            palette: default_palette(),
+        }
+    }
+}
+
+// Function `default_palette` returns the palette MagicaVoxel uses for files without one:
+// a cube of six levels per channel, followed by ramps of red, green, blue and gray.
+fn default_palette() -> [Color; 256] {
+    const LEVELS: [u8; 6] = [0xff, 0xcc, 0x99, 0x66, 0x33, 0x00];
+    const RAMP: [u8; 10] = [0xee, 0xdd, 0xbb, 0xaa, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+
+    let mut palette = [Color::default(); 256];
+    let mut colors = Vec::with_capacity(255);
+    for red in LEVELS {
+        for green in LEVELS {
+            for blue in LEVELS {
+                colors.push(Color::new(red, green, blue));
+            }
+        }
+    }
+    // The cube ends with black, which the ramps replace.
+    colors.pop();
+    for ramp in [
+        |level| Color::new(level, 0, 0),
+        |level| Color::new(0, level, 0),
+        |level| Color::new(0, 0, level),
+        |level| Color::new(level, level, level),
+    ] {
+        colors.extend(RAMP.map(ramp));
+    }
+
+    palette[1..].copy_from_slice(&colors);
+    palette
+}
+
+// Function `invalid` creates the error returned for malformed files.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Private struct `Reader` reading the little endian values of a file held in memory.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> io::Result<&'a [u8]> {
+        if count > self.bytes.len() {
+            return Err(invalid("unexpected end of file"));
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn count(&mut self) -> io::Result<usize> {
+        usize::try_from(self.i32()?).map_err(|_| invalid("negative count"))
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let length = self.count()?;
+        Ok(String::from_utf8_lossy(self.take(length)?).into_owned())
+    }
+
+    fn dict(&mut self) -> io::Result<HashMap<String, String>> {
+        (0..self.count()?)
+            .map(|_| Ok((self.string()?, self.string()?)))
+            .collect()
+    }
+}
+
+// Private enum `Node` describing the nodes of the scene graph that place models in the scene.
+enum Node {
+    Transform { child: i32, translation: [i32; 3] },
+    Group { children: Vec<i32> },
+    Shape { models: Vec<usize> },
+}
+
+impl VoxFile {
+    /// Public method `read` parses a MagicaVoxel file.
+    /// Every model is read along with its translation in the scene graph, rotations of the scene graph
+    /// and chunks describing materials, layers and cameras are ignored.
+    pub fn read(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != b"VOX " {
+            return Err(invalid("not a MagicaVoxel file"));
+        }
+        reader.i32()?;
+
+        let mut file = VoxFile::default();
+        let mut nodes = HashMap::new();
+        let mut size = None;
+
+        // The chunks of the file are the children of the main chunk, which have no children of their own.
+        if reader.take(4)? != b"MAIN" {
+            return Err(invalid("missing main chunk"));
+        }
+        let content = reader.count()?;
+        reader.take(content)?;
+        let children = reader.count()?;
+        let mut reader = Reader {
+            bytes: reader.take(children)?,
+        };
+
+        while !reader.bytes.is_empty() {
+            let id = reader.take(4)?;
+            let content = reader.count()?;
+            let children = reader.count()?;
+            let mut chunk = Reader {
+                bytes: reader.take(content)?,
+            };
+            reader.take(children)?;
+
+            match id {
+                b"SIZE" => {
+                    let read = [chunk.i32()?, chunk.i32()?, chunk.i32()?];
+                    // Sizes are checked before anything is allocated for the model.
+                    if read
+                        .iter()
+                        .any(|size| !(0..=MAX_VOX_SIZE as i32).contains(size))
+                    {
+                        return Err(invalid("model size out of range"));
+                    }
+                    size = Some(read.map(|size| size as u32));
+                }
+                b"XYZI" => {
+                    let size = size
+                        .take()
+                        .ok_or_else(|| invalid("voxels without a size"))?;
+                    // Every voxel takes 4 bytes, which bounds the count before it is allocated.
+                    let count = chunk.count()?;
+                    if count > chunk.bytes.len() / 4 {
+                        return Err(invalid("unexpected end of file"));
+                    }
+                    let voxels = (0..count)
+                        .map(|_| {
+                            let bytes = chunk.take(4)?;
+                            Ok(([bytes[0], bytes[1], bytes[2]], bytes[3]))
+                        })
+                        .collect::<io::Result<Vec<_>>>()?;
+                    file.models.push(VoxModel {
+                        size,
+                        voxels,
+                        translation: size.map(|size| (size / 2) as i32),
+                    });
+                }
+                b"RGBA" => {
+                    // Color index `i` is stored at position `i - 1`, the last color is unused.
+                    for index in 1..256 {
+                        let bytes = chunk.take(4)?;
+                        file.palette[index] = Color {
+                            red: bytes[0],
+                            green: bytes[1],
+                            blue: bytes[2],
+                            alpha: bytes[3],
+                        };
+                    }
+                }
+                b"nTRN" => {
+                    let id = chunk.i32()?;
+                    chunk.dict()?;
+                    let child = chunk.i32()?;
+                    // The reserved id and the layer.
+                    chunk.take(8)?;
+                    let frames = chunk.count()?;
+                    let mut translation = [0; 3];
+                    if frames > 0 {
+                        if let Some(t) = chunk.dict()?.get("_t") {
+                            let axes = t
+                                .split_whitespace()
+                                .map(|value| value.parse::<i32>())
+                                .collect::<Result<Vec<_>, _>>();
+                            translation = match axes.as_deref() {
+                                Ok(&[x, y, z]) => [x, y, z],
+                                _ => return Err(invalid("malformed translation")),
+                            };
+                        }
+                    }
+                    nodes.insert(id, Node::Transform { child, translation });
+                }
+                b"nGRP" => {
+                    let id = chunk.i32()?;
+                    chunk.dict()?;
+                    let count = chunk.count()?;
+                    if count > chunk.bytes.len() / 4 {
+                        return Err(invalid("unexpected end of file"));
+                    }
+                    let children = (0..count)
+                        .map(|_| chunk.i32())
+                        .collect::<io::Result<Vec<_>>>()?;
+                    nodes.insert(id, Node::Group { children });
+                }
+                b"nSHP" => {
+                    let id = chunk.i32()?;
+                    chunk.dict()?;
+                    let mut models = Vec::new();
+                    for _ in 0..chunk.count()? {
+                        models.push(chunk.count()?);
+                        chunk.dict()?;
+                    }
+                    nodes.insert(id, Node::Shape { models });
+                }
+                _ => {}
+            }
+        }
+
+        // Walk the scene graph from its root, adding up the translations on the way to every model.
+        let mut stack = vec![(0, [0i32; 3])];
+        let mut visited = HashSet::new();
+        while let Some((id, translation)) = stack.pop() {
+            // Guard against cycles in malformed files.
+            if !visited.insert(id) {
+                continue;
+            }
+            match nodes.get(&id) {
+                Some(Node::Transform {
+                    child,
+                    translation: offset,
+                }) => {
+                    let mut sum = [0; 3];
+                    for axis in 0..3 {
+                        sum[axis] = translation[axis]
+                            .checked_add(offset[axis])
+                            .ok_or_else(|| invalid("translation out of range"))?;
+                    }
+                    stack.push((*child, sum))
+                }
+                Some(Node::Group { children }) => {
+                    stack.extend(children.iter().map(|child| (*child, translation)))
+                }
+                Some(Node::Shape { models }) => {
+                    for model in models {
+                        if let Some(model) = file.models.get_mut(*model) {
+                            model.translation = translation;
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Ok(file)
+    }
+
+    /// Public method `from_schematics` converts schematics to a file, each placed with its minimum corner
+    /// at a world voxel position. Fails if a schematic is larger than `MAX_VOX_SIZE` along any axis,
+    /// or if the schematics use more than 255 colors. Liquids are exported as their color, losing their level.
+    pub fn from_schematics(schematics: &[(Schematic, WorldVoxelPos)]) -> io::Result<Self> {
+        let mut file = VoxFile {
+            models: Vec::new(),
+            palette: [Color::default(); 256],
+        };
+        let mut indices: HashMap<Color, u8> = HashMap::new();
+
+        for (schematic, origin) in schematics {
+            let [sx, sy, sz] = schematic.size;
+            if schematic.size.iter().any(|size| *size > MAX_VOX_SIZE) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "schematic too large for a MagicaVoxel model",
+                ));
+            }
+
+            let mut voxels = Vec::new();
+            for (i, voxel) in schematic.voxels.iter().enumerate() {
+                let Some(color) = voxel.color() else {
+                    continue;
+                };
+                let index = match indices.get(&color) {
+                    Some(index) => *index,
+                    None if indices.len() < 255 => {
+                        let index = indices.len() as u8 + 1;
+                        indices.insert(color, index);
+                        file.palette[index as usize] = color;
+                        index
+                    }
+                    None => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "more than 255 colors",
+                        ))
+                    }
+                };
+
+                // Our Y axis is MagicaVoxel's Z axis, and our Z axis its negative Y axis.
+                let [x, y, z] = schematic.delinearize(i);
+                voxels.push(([x as u8, (sz - 1 - z) as u8, y as u8], index));
+            }
+
+            let size = [sx as u32, sz as u32, sy as u32];
+            let out_of_range = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "schematic position out of range",
+                )
+            };
+            let max_z = origin
+                .z
+                .checked_add(sz as i32 - 1)
+                .and_then(i32::checked_neg)
+                .ok_or_else(out_of_range)?;
+            let min = [origin.x, max_z, origin.y];
+            let mut translation = [0; 3];
+            for axis in 0..3 {
+                translation[axis] = min[axis]
+                    .checked_add((size[axis] / 2) as i32)
+                    .ok_or_else(out_of_range)?;
+            }
+            file.models.push(VoxModel {
+                size,
+                voxels,
+                translation,
+            });
+        }
+
+        Ok(file)
+    }
+
+    // Public method `copy` converts the box between two world voxel positions to a file with a single model.
+    pub fn copy(world: &VoxelWorld, a: WorldVoxelPos, b: WorldVoxelPos) -> io::Result<Self> {
+        Self::from_schematics(&[(Schematic::copy(world, a, b), a.min(b))])
+    }
+
+    // Public method `schematics` converts every model to a schematic, along with the world voxel position
+    // its minimum corner maps to. Fails if a model lies outside the range of an `i32`.
+    pub fn schematics(&self) -> io::Result<Vec<(Schematic, WorldVoxelPos)>> {
+        self.models
+            .iter()
+            .map(|model| Ok((model.to_schematic(&self.palette), model.origin()?)))
+            .collect()
+    }
+
+    /// Public method `paste` writes every model into the world at its place in the scene, offset by `origin`,
+    /// and returns a record of the voxels that changed. Empty voxels of the models leave the world untouched.
+    /// The voxels of all models are relit and notified together. Fails without changing the world
+    /// if a model would land outside the range of an `i32`.
+    pub fn paste(&self, world: &mut VoxelWorld, origin: WorldVoxelPos) -> io::Result<EditRecord> {
+        let mut positions = Vec::new();
+        for (schematic, position) in self.schematics()? {
+            let position = [
+                origin.x.checked_add(position.x),
+                origin.y.checked_add(position.y),
+                origin.z.checked_add(position.z),
+            ];
+            match position {
+                [Some(x), Some(y), Some(z)] => {
+                    positions.push((schematic, WorldVoxelPos { x, y, z }))
+                }
+                _ => return Err(invalid("model position out of range")),
+            }
+        }
+
+        let mut record = EditRecord::default();
+        for (schematic, position) in positions {
+            let pasted = schematic.paste_quietly(world, position, true);
+            record.changes.extend(pasted.changes);
+        }
+
+        world.notify(&record);
+        Ok(record)
+    }
+
+    /// Public method `write` writes the file with every model placed by its translation in a minimal scene graph:
+    /// a root transform and group holding a transform and shape for every model.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut chunks = Vec::new();
+        let chunk = |chunks: &mut Vec<u8>, id: &[u8; 4], content: Vec<u8>| {
+            chunks.extend_from_slice(id);
+            chunks.extend_from_slice(&(content.len() as i32).to_le_bytes());
+            chunks.extend_from_slice(&0i32.to_le_bytes());
+            chunks.extend_from_slice(&content);
+        };
+        let ints = |values: &[i32]| -> Vec<u8> {
+            values
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect()
+        };
+        let string = |content: &mut Vec<u8>, value: &str| {
+            content.extend_from_slice(&(value.len() as i32).to_le_bytes());
+            content.extend_from_slice(value.as_bytes());
+        };
+
+        for model in self.models.iter() {
+            let [x, y, z] = model.size.map(|size| size as i32);
+            chunk(&mut chunks, b"SIZE", ints(&[x, y, z]));
+
+            let mut content = ints(&[model.voxels.len() as i32]);
+            for ([x, y, z], index) in model.voxels.iter() {
+                content.extend_from_slice(&[*x, *y, *z, *index]);
+            }
+            chunk(&mut chunks, b"XYZI", content);
+        }
+
+        // Node 0 is the root transform, node 1 the group, and every model gets a transform and a shape.
+        let transform = |id: i32, child: i32, layer: i32, translation: Option<[i32; 3]>| {
+            let mut content = ints(&[id, 0, child, -1, layer, 1]);
+            match translation {
+                Some([x, y, z]) => {
+                    content.extend_from_slice(&1i32.to_le_bytes());
+                    string(&mut content, "_t");
+                    string(&mut content, &format!("{x} {y} {z}"));
+                }
+                None => content.extend_from_slice(&0i32.to_le_bytes()),
+            }
+            content
+        };
+        chunk(&mut chunks, b"nTRN", transform(0, 1, -1, None));
+        let children: Vec<i32> = (0..self.models.len() as i32).map(|i| 2 + 2 * i).collect();
+        let mut group = ints(&[1, 0, children.len() as i32]);
+        group.extend(ints(&children));
+        chunk(&mut chunks, b"nGRP", group);
+        for (i, model) in self.models.iter().enumerate() {
+            let id = 2 + 2 * i as i32;
+            chunk(
+                &mut chunks,
+                b"nTRN",
+                transform(id, id + 1, 0, Some(model.translation)),
+            );
+            chunk(&mut chunks, b"nSHP", ints(&[id + 1, 0, 1, i as i32, 0]));
+        }
+
+        let mut palette = Vec::with_capacity(256 * 4);
+        for color in self.palette[1..].iter().chain([Color::default()].iter()) {
+            palette.extend_from_slice(&[color.red, color.green, color.blue, color.alpha]);
+        }
+        chunk(&mut chunks, b"RGBA", palette);
+
+        writer.write_all(b"VOX ")?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(b"MAIN")?;
+        writer.write_all(&0i32.to_le_bytes())?;
+        writer.write_all(&(chunks.len() as i32).to_le_bytes())?;
+        writer.write_all(&chunks)
+    }
+
+    // Public method `load` reads a MagicaVoxel file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(&fs::read(path)?)
+    }
+
+    // Public method `save` writes a MagicaVoxel file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+        fs::write(path, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::WorldConfig;
+
+    // Function `round_trip` writes a file and reads it back.
+    fn round_trip(file: &VoxFile) -> VoxFile {
+        let mut bytes = Vec::new();
+        file.write(&mut bytes).unwrap();
+        VoxFile::read(&bytes).unwrap()
+    }
+
+    // Function `schematic` creates a schematic of the given size with a few voxels set.
+    fn schematic(size: [usize; 3], voxels: &[([usize; 3], Voxel)]) -> Schematic {
+        let mut schematic = Schematic::new(size);
+        for (position, voxel) in voxels {
+            let i = schematic.linearize(*position);
+            schematic.voxels[i] = *voxel;
+        }
+        schematic
+    }
+
+    #[test]
+    fn schematics_round_trip_with_their_origins() {
+        let red = Voxel::Opaque(Color::new(200, 10, 10));
+        let green = Voxel::Opaque(Color::new(10, 200, 10));
+        let glass = Voxel::Transparent(Color {
+            red: 100,
+            green: 150,
+            blue: 250,
+            alpha: 128,
+        });
+        let schematics = [
+            (
+                schematic(
+                    [3, 2, 4],
+                    &[([0, 0, 0], red), ([2, 1, 3], green), ([1, 0, 2], glass)],
+                ),
+                WorldVoxelPos::new(-5, 2, 7),
+            ),
+            (
+                schematic([1, 5, 1], &[([0, 4, 0], red), ([0, 0, 0], glass)]),
+                WorldVoxelPos::new(10, -3, -8),
+            ),
+        ];
+
+        let file = VoxFile::from_schematics(&schematics).unwrap();
+        let read = round_trip(&file);
+        assert_eq!(read, file);
+        assert_eq!(read.models.len(), 2);
+        assert_eq!(read.palette[1..4], file.palette[1..4]);
+        assert_eq!(read.schematics().unwrap(), schematics);
+    }
+
+    #[test]
+    fn custom_palette_round_trips() {
+        let mut file = VoxFile::default();
+        file.palette[1] = Color::new(1, 2, 3);
+        file.palette[255] = Color {
+            red: 4,
+            green: 5,
+            blue: 6,
+            alpha: 7,
+        };
+        file.models.push(VoxModel {
+            size: [2, 2, 1],
+            voxels: vec![([0, 0, 0], 1), ([1, 1, 0], 255)],
+            translation: [1, 1, 0],
+        });
+
+        let read = round_trip(&file);
+        assert_eq!(read, file);
+
+        // MagicaVoxel's Y axis becomes our negative Z axis.
+        let schematic = read.models[0].to_schematic(&read.palette);
+        assert_eq!(schematic.size, [2, 1, 2]);
+        assert_eq!(
+            schematic.voxels[schematic.linearize([0, 0, 1])],
+            Voxel::Opaque(Color::new(1, 2, 3))
+        );
+        assert_eq!(
+            schematic.voxels[schematic.linearize([1, 0, 0])],
+            Voxel::Transparent(file.palette[255])
+        );
+        assert_eq!(
+            schematic
+                .voxels
+                .iter()
+                .filter(|voxel| **voxel != Voxel::Empty)
+                .count(),
+            2
+        );
+    }
+
+    // Function `with_root_translation` rewrites the root transform of a written file, which is the first
+    // transform when the models have no voxels, to translate the whole scene by `t`.
+    fn with_root_translation(bytes: &[u8], t: &str) -> Vec<u8> {
+        let start = bytes.windows(4).position(|id| id == b"nTRN").unwrap();
+        let mut content: Vec<u8> = [0i32, 0, 1, -1, -1, 1, 1]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        for text in ["_t", t] {
+            content.extend_from_slice(&(text.len() as i32).to_le_bytes());
+            content.extend_from_slice(text.as_bytes());
+        }
+        let mut chunk = b"nTRN".to_vec();
+        chunk.extend_from_slice(&(content.len() as i32).to_le_bytes());
+        chunk.extend_from_slice(&0i32.to_le_bytes());
+        chunk.extend_from_slice(&content);
+
+        // The root transform without a translation takes 12 bytes of header and 28 of content.
+        let mut rewritten = [&bytes[..start], &chunk, &bytes[start + 40..]].concat();
+        let children = (rewritten.len() - 20) as i32;
+        rewritten[16..20].copy_from_slice(&children.to_le_bytes());
+        rewritten
+    }
+
+    // Function `single_model` writes a file with a single empty model.
+    fn single_model(size: [u32; 3], translation: [i32; 3]) -> Vec<u8> {
+        let mut file = VoxFile::default();
+        file.models.push(VoxModel {
+            size,
+            voxels: Vec::new(),
+            translation,
+        });
+        let mut bytes = Vec::new();
+        file.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn scene_translations_add_up() {
+        let bytes = with_root_translation(&single_model([2, 2, 2], [1, 2, 3]), "10 -20 30");
+        let read = VoxFile::read(&bytes).unwrap();
+        assert_eq!(read.models[0].translation, [11, -18, 33]);
+        assert_eq!(read.models[0].min().unwrap(), [10, -19, 32]);
+    }
+
+    #[test]
+    fn malformed_translations_are_rejected() {
+        let bytes = single_model([2, 2, 2], [1, 2, 3]);
+        for t in ["1 x 3", "1 2", "1 2 3 4", "2147483648 0 0", ""] {
+            let error = VoxFile::read(&with_root_translation(&bytes, t)).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{t}");
+        }
+    }
+
+    #[test]
+    fn overflowing_translations_are_rejected() {
+        // Translations adding up past the range of an `i32` in the scene graph.
+        let bytes = with_root_translation(&single_model([2, 2, 2], [i32::MAX, 0, 0]), "1 0 0");
+        let error = VoxFile::read(&bytes).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // Models whose corners lie past the range of an `i32`, which fail before anything is pasted.
+        let mut world = VoxelWorld::new(WorldConfig::new(8, 1.0));
+        for (size, translation) in [([2, 1, 1], [i32::MIN, 0, 0]), ([1, 3, 1], [0, i32::MAX, 0])] {
+            let mut file = VoxFile::default();
+            file.models.push(VoxModel {
+                size,
+                voxels: vec![([0, 0, 0], 1)],
+                translation,
+            });
+            assert!(file.models[0].origin().is_err(), "{translation:?}");
+            assert!(file.schematics().is_err(), "{translation:?}");
+            assert!(file.paste(&mut world, WorldVoxelPos::default()).is_err());
+        }
+
+        // A model fitting the range, pasted at an origin that pushes it past it.
+        let mut file = VoxFile::default();
+        file.models.push(VoxModel {
+            size: [1, 1, 1],
+            voxels: vec![([0, 0, 0], 1)],
+            translation: [1, 0, 0],
+        });
+        let origin = WorldVoxelPos::new(i32::MAX, 0, 0);
+        assert!(file.models[0].origin().is_ok());
+        assert!(file.paste(&mut world, origin).is_err());
+
+        // Schematics placed past the range are rejected on export.
+        let schematic = Schematic::new([1, 1, 2]);
+        let origin = WorldVoxelPos::new(0, 0, i32::MAX);
+        assert!(VoxFile::from_schematics(&[(schematic, origin)]).is_err());
+    }
+
+    #[test]
+    fn oversized_models_are_rejected() {
+        for size in [MAX_VOX_SIZE as i32 + 1, -1, i32::MAX] {
+            let mut file = VoxFile::default();
+            file.models.push(VoxModel {
+                size: [1, 1, 1],
+                voxels: Vec::new(),
+                translation: [0; 3],
+            });
+            let mut bytes = Vec::new();
+            file.write(&mut bytes).unwrap();
+
+            // The size of the only model follows the header and the main chunk.
+            let offset = 20 + 12 + 4;
+            bytes[offset..offset + 4].copy_from_slice(&size.to_le_bytes());
+            let error = VoxFile::read(&bytes).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{size}");
+        }
+    }
+}
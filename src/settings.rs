@@ -0,0 +1,63 @@
+use bevy::{
+    app::{App, Plugin},
+    ecs::system::Resource,
+};
+
+use crate::{
+    fluid::SEA_LEVEL,
+    mesh::{GenerationSettings, CHUNK_SIZE, VOXEL_SIZE},
+    world::{WorldConfig, RENDER_DISTANCE},
+};
+
+// Public struct `VoxelSettings` holding the options consumers of the engine configure, instead of editing constants.
+// The settings are read whenever they are needed, so changing the resource affects chunks generated afterwards,
+// except for the chunk size and voxel scale which are fixed when `WorldPlugin` creates the world.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct VoxelSettings {
+    // The number of voxels along each axis of a chunk, a multiple of `2^MAX_LOD`.
+    pub chunk_size: usize,
+    // The size of a voxel in world units.
+    pub voxel_size: f32,
+    // The seed of the noise shaping the terrain.
+    pub seed: u32,
+    // The height below which empty space is filled with water, in voxels.
+    pub sea_level: i32,
+    // The number of chunks loaded around the player in every direction.
+    pub render_distance: i32,
+}
+
+impl VoxelSettings {
+    // Public method `config` returns the chunk size and voxel scale of the world.
+    pub fn config(&self) -> WorldConfig {
+        WorldConfig::new(self.chunk_size, self.voxel_size)
+    }
+
+    // Public method `generation` returns the options used when generating the voxels of new chunks.
+    pub fn generation(&self) -> GenerationSettings {
+        GenerationSettings {
+            seed: self.seed,
+            sea_level: self.sea_level,
+        }
+    }
+}
+
+impl Default for VoxelSettings {
+    fn default() -> Self {
+        Self {
+            chunk_size: CHUNK_SIZE,
+            voxel_size: VOXEL_SIZE,
+            seed: 1,
+            sea_level: SEA_LEVEL,
+            render_distance: RENDER_DISTANCE,
+        }
+    }
+}
+
+// Public struct `SettingsPlugin` inserting the settings the rest of the plugins read.
+pub struct SettingsPlugin(pub VoxelSettings);
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.0);
+    }
+}
@@ -3,5 +3,5 @@ pub mod position;
 pub mod visibility;
 
 pub use color::Color;
-pub use position::Position;
+pub use position::{ChunkPos, LocalPos, WorldVoxelPos, FACE_OFFSETS};
 pub use visibility::{Visibility, EMPTY, OPAQUE, TRANSPARENT};
@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Hash)]
 pub struct Color {
     pub red: u8,   // The red component of the color
     pub green: u8, // The green component of the color
@@ -31,6 +31,7 @@ impl Color {
 }
 
 // Implement the `From` trait for converting a `Color` to a `bevy::render::color::Color`
+#[cfg(feature = "bevy")]
 impl From<Color> for bevy::render::color::Color {
     // The conversion is done by creating a new `bevy::render::color::Color` with the RGBA components of the `Color`
     // Each component is divided by 255.0 to normalize it to the range [0.0, 1.0]
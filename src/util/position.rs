@@ -1,18 +1,457 @@
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use glam::Vec3;
+
+/// The offsets of the six neighbors sharing a face with a position, in the same order as `Side`:
+/// X-, X+, Y-, Y+, Z-, Z+.
+pub const FACE_OFFSETS: [[i32; 3]; 6] = [
+    [-1, 0, 0],
+    [1, 0, 0],
+    [0, -1, 0],
+    [0, 1, 0],
+    [0, 0, -1],
+    [0, 0, 1],
+];
+
+// Macro `grid_position` implements what world voxel positions and chunk positions have in common:
+// constructors, offsets, distances, neighbors, arithmetic and conversions from and to arrays.
+// The two are kept as distinct types so one can't be passed where the other is expected.
+macro_rules! grid_position {
+    ($name:ident) => {
+        impl $name {
+            // A constant function to create a position from its coordinates.
+            pub const fn new(x: i32, y: i32, z: i32) -> Self {
+                Self { x, y, z }
+            }
+
+            // Method to calculate the Euclidean distance between this position and another
+            pub fn distance(&self, other: &Self) -> f64 {
+                let dx = other.x - self.x; // Calculate the difference in x-coordinates
+                let dy = other.y - self.y; // Calculate the difference in y-coordinates
+                let dz = other.z - self.z; // Calculate the difference in z-coordinates
+
+                // Square the differences, sum them, cast to f64, and take the square root to get the distance
+                ((dx * dx + dy * dy + dz * dz) as f64).sqrt()
+            }
+
+            // Method to move this position by an offset, such as the offset of a side
+            pub fn offset(&self, [dx, dy, dz]: [i32; 3]) -> Self {
+                Self {
+                    x: self.x + dx,
+                    y: self.y + dy,
+                    z: self.z + dz,
+                }
+            }
+
+            // Method returning the six positions sharing a face with this one, in the order of `FACE_OFFSETS`.
+            pub fn neighbors(self) -> impl Iterator<Item = Self> {
+                FACE_OFFSETS
+                    .into_iter()
+                    .map(move |offset| self.offset(offset))
+            }
+
+            // Method returning the 26 positions sharing a face, an edge or a corner with this one.
+            pub fn surrounding(self) -> impl Iterator<Item = Self> {
+                (-1..=1).flat_map(move |dz| {
+                    (-1..=1).flat_map(move |dy| {
+                        (-1..=1)
+                            .filter(move |dx| [*dx, dy, dz] != [0; 3])
+                            .map(move |dx| self.offset([dx, dy, dz]))
+                    })
+                })
+            }
+
+            // Method returning the smallest coordinates of this position and another along each axis.
+            pub fn min(self, other: Self) -> Self {
+                Self::new(
+                    self.x.min(other.x),
+                    self.y.min(other.y),
+                    self.z.min(other.z),
+                )
+            }
+
+            // Method returning the largest coordinates of this position and another along each axis.
+            pub fn max(self, other: Self) -> Self {
+                Self::new(
+                    self.x.max(other.x),
+                    self.y.max(other.y),
+                    self.z.max(other.z),
+                )
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+            }
+        }
+
+        impl Add<[i32; 3]> for $name {
+            type Output = Self;
+
+            fn add(self, offset: [i32; 3]) -> Self {
+                self.offset(offset)
+            }
+        }
+
+        impl Sub<[i32; 3]> for $name {
+            type Output = Self;
+
+            fn sub(self, [dx, dy, dz]: [i32; 3]) -> Self {
+                self.offset([-dx, -dy, -dz])
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self::new(-self.x, -self.y, -self.z)
+            }
+        }
+
+        impl From<[i32; 3]> for $name {
+            fn from([x, y, z]: [i32; 3]) -> Self {
+                Self::new(x, y, z)
+            }
+        }
+
+        impl From<$name> for [i32; 3] {
+            fn from(position: $name) -> Self {
+                [position.x, position.y, position.z]
+            }
+        }
+    };
+}
+
+// Public struct `WorldVoxelPos` holding the position of a voxel in the world, counted in voxels.
+// Voxel `n` is centered on `n * voxel_size` in world space.
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash)]
-pub struct Position {
-    pub x: i32, // The x-coordinate of the position
-    pub y: i32, // The y-coordinate of the position
-    pub z: i32, // The z-coordinate of the position
+pub struct WorldVoxelPos {
+    pub x: i32, // The x-coordinate of the voxel
+    pub y: i32, // The y-coordinate of the voxel
+    pub z: i32, // The z-coordinate of the voxel
+}
+
+grid_position!(WorldVoxelPos);
+
+impl WorldVoxelPos {
+    // Method returning the position of the chunk owning this voxel, rounding towards negative infinity.
+    pub fn chunk(&self, chunk_size: usize) -> ChunkPos {
+        let size = chunk_size as i32;
+        ChunkPos::new(
+            self.x.div_euclid(size),
+            self.y.div_euclid(size),
+            self.z.div_euclid(size),
+        )
+    }
+
+    // Method returning the position of this voxel within the chunk owning it.
+    pub fn local(&self, chunk_size: usize) -> LocalPos {
+        let size = chunk_size as i32;
+        LocalPos::new(
+            self.x.rem_euclid(size) as usize,
+            self.y.rem_euclid(size) as usize,
+            self.z.rem_euclid(size) as usize,
+        )
+    }
+
+    // Method splitting this voxel into the position of the chunk owning it and its position within that chunk.
+    pub fn split(&self, chunk_size: usize) -> (ChunkPos, LocalPos) {
+        (self.chunk(chunk_size), self.local(chunk_size))
+    }
+
+    // Method returning the center of this voxel in world space.
+    pub fn center(&self, voxel_size: f32) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32) * voxel_size
+    }
+
+    // Method returning the voxel a point in world space lies in.
+    pub fn at(point: Vec3, voxel_size: f32) -> Self {
+        let voxel = (point / voxel_size).round().as_ivec3();
+        Self::new(voxel.x, voxel.y, voxel.z)
+    }
+}
+
+// Public struct `ChunkPos` holding the position of a chunk in the world, counted in chunks.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct ChunkPos {
+    pub x: i32, // The x-coordinate of the chunk
+    pub y: i32, // The y-coordinate of the chunk
+    pub z: i32, // The z-coordinate of the chunk
+}
+
+grid_position!(ChunkPos);
+
+impl ChunkPos {
+    // Method returning the world voxel position of the first voxel of this chunk.
+    pub fn origin(&self, chunk_size: usize) -> WorldVoxelPos {
+        let size = chunk_size as i32;
+        WorldVoxelPos::new(self.x * size, self.y * size, self.z * size)
+    }
+
+    // Method returning the world voxel position of a voxel within this chunk.
+    pub fn join(&self, local: LocalPos, chunk_size: usize) -> WorldVoxelPos {
+        self.origin(chunk_size)
+            .offset([local.x as i32, local.y as i32, local.z as i32])
+    }
+
+    // Method returning where this chunk starts in world space, `chunk_extent` being its size in world units.
+    pub fn translation(&self, chunk_extent: f32) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32) * chunk_extent
+    }
+
+    // Method returning the chunk owning the voxel a point in world space lies in, see `WorldVoxelPos::at`.
+    pub fn at(point: Vec3, voxel_size: f32, chunk_size: usize) -> Self {
+        WorldVoxelPos::at(point, voxel_size).chunk(chunk_size)
+    }
+}
+
+// Public struct `LocalPos` holding the position of a voxel within its chunk, from 0 to the chunk size excluded.
+// Chunk meshes store a padding of one voxel on every side, see `padded` to index them.
+#[derive(Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+pub struct LocalPos {
+    pub x: usize, // The x-coordinate of the voxel within its chunk
+    pub y: usize, // The y-coordinate of the voxel within its chunk
+    pub z: usize, // The z-coordinate of the voxel within its chunk
+}
+
+impl LocalPos {
+    // A constant function to create a local position from its coordinates.
+    pub const fn new(x: usize, y: usize, z: usize) -> Self {
+        Self { x, y, z }
+    }
+
+    // Method returning the coordinates of this voxel in the padded voxels of a chunk mesh.
+    pub fn padded(&self) -> [usize; 3] {
+        [self.x + 1, self.y + 1, self.z + 1]
+    }
+
+    // Method converting padded coordinates back into a local position, `None` for voxels in the padding.
+    pub fn from_padded([x, y, z]: [usize; 3], chunk_size: usize) -> Option<Self> {
+        let inside = |p: usize| p >= 1 && p <= chunk_size;
+        (inside(x) && inside(y) && inside(z)).then(|| Self::new(x - 1, y - 1, z - 1))
+    }
+
+    // Method moving this position by an offset, `None` if it leaves the chunk.
+    pub fn offset(&self, [dx, dy, dz]: [i32; 3], chunk_size: usize) -> Option<Self> {
+        let axis = |p: usize, d: i32| p.checked_add_signed(d as isize).filter(|p| *p < chunk_size);
+        Some(Self::new(
+            axis(self.x, dx)?,
+            axis(self.y, dy)?,
+            axis(self.z, dz)?,
+        ))
+    }
+
+    // Method returning the index of this voxel in a chunk of `chunk_size` voxels along each axis, x first.
+    pub fn index(&self, chunk_size: usize) -> usize {
+        self.x + chunk_size * (self.y + chunk_size * self.z)
+    }
+}
+
+impl From<[usize; 3]> for LocalPos {
+    fn from([x, y, z]: [usize; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl From<LocalPos> for [usize; 3] {
+    fn from(local: LocalPos) -> Self {
+        [local.x, local.y, local.z]
+    }
 }
 
-impl Position {
-    // Method to calculate the Euclidean distance between this position and another
-    pub fn distance(&self, other: &Self) -> f64 {
-        let dx = other.x - self.x; // Calculate the difference in x-coordinates
-        let dy = other.y - self.y; // Calculate the difference in y-coordinates
-        let dz = other.z - self.z; // Calculate the difference in z-coordinates
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 16;
+
+    #[test]
+    fn split_floors_negative_coordinates() {
+        let cases = [
+            (0, 0, 0),
+            (15, 0, 15),
+            (16, 1, 0),
+            (-1, -1, 15),
+            (-16, -1, 0),
+            (-17, -2, 15),
+        ];
+        for (world, chunk, local) in cases {
+            let position = WorldVoxelPos::new(world, world, world);
+            let (chunk_position, local_position) = position.split(SIZE);
+            assert_eq!(
+                chunk_position,
+                ChunkPos::new(chunk, chunk, chunk),
+                "{world}"
+            );
+            assert_eq!(
+                local_position,
+                LocalPos::new(local, local, local),
+                "{world}"
+            );
+            assert_eq!(position.chunk(SIZE), chunk_position);
+            assert_eq!(position.local(SIZE), local_position);
+            assert_eq!(chunk_position.join(local_position, SIZE), position);
+        }
+    }
+
+    #[test]
+    fn split_handles_axes_independently() {
+        let position = WorldVoxelPos::new(-1, 16, -17);
+        assert_eq!(
+            position.split(SIZE),
+            (ChunkPos::new(-1, 1, -2), LocalPos::new(15, 0, 15))
+        );
+    }
+
+    #[test]
+    fn origin_is_the_first_voxel_of_the_chunk() {
+        assert_eq!(
+            ChunkPos::new(-1, 0, 2).origin(SIZE),
+            WorldVoxelPos::new(-16, 0, 32)
+        );
+        assert_eq!(
+            ChunkPos::new(-1, 0, 2).join(LocalPos::new(15, 1, 0), SIZE),
+            WorldVoxelPos::new(-1, 1, 32)
+        );
+    }
+
+    #[test]
+    fn neighbors_follow_face_offsets() {
+        let neighbors: Vec<_> = WorldVoxelPos::new(0, -1, 5).neighbors().collect();
+        assert_eq!(
+            neighbors,
+            [
+                WorldVoxelPos::new(-1, -1, 5),
+                WorldVoxelPos::new(1, -1, 5),
+                WorldVoxelPos::new(0, -2, 5),
+                WorldVoxelPos::new(0, 0, 5),
+                WorldVoxelPos::new(0, -1, 4),
+                WorldVoxelPos::new(0, -1, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn surrounding_skips_the_position_itself() {
+        let position = ChunkPos::new(-1, 0, 1);
+        let surrounding: Vec<_> = position.surrounding().collect();
+        assert_eq!(surrounding.len(), 26);
+        assert!(!surrounding.contains(&position));
+        assert!(surrounding.contains(&ChunkPos::new(-2, -1, 0)));
+        assert!(surrounding.contains(&ChunkPos::new(0, 1, 2)));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = WorldVoxelPos::new(1, -2, 3);
+        let b = WorldVoxelPos::new(-4, 5, -6);
+        assert_eq!(a + b, WorldVoxelPos::new(-3, 3, -3));
+        assert_eq!(a - b, WorldVoxelPos::new(5, -7, 9));
+        assert_eq!(a + [1, 1, -1], WorldVoxelPos::new(2, -1, 2));
+        assert_eq!(a - [1, 1, -1], WorldVoxelPos::new(0, -3, 4));
+        assert_eq!(-a, WorldVoxelPos::new(-1, 2, -3));
+        assert_eq!(a.min(b), WorldVoxelPos::new(-4, -2, -6));
+        assert_eq!(a.max(b), WorldVoxelPos::new(1, 5, 3));
+
+        let mut c = a;
+        c += b;
+        c -= b;
+        assert_eq!(c, a);
+        assert_eq!(<[i32; 3]>::from(a), [1, -2, 3]);
+        assert_eq!(WorldVoxelPos::from([1, -2, 3]), a);
+    }
+
+    #[test]
+    fn world_space_conversions() {
+        let voxel_size = 0.5;
+        let position = WorldVoxelPos::new(-3, 0, 2);
+        assert_eq!(position.center(voxel_size), Vec3::new(-1.5, 0.0, 1.0));
+        assert_eq!(
+            WorldVoxelPos::at(position.center(voxel_size), voxel_size),
+            position
+        );
+        // Voxels are centered on their position, so they reach half a voxel to each side.
+        assert_eq!(
+            WorldVoxelPos::at(Vec3::new(-0.2, 0.2, -0.26), voxel_size),
+            WorldVoxelPos::new(0, 0, -1)
+        );
+    }
+
+    #[test]
+    fn chunk_at_agrees_with_voxel_at() {
+        let voxel_size = 0.5;
+        // The first voxel of chunk 0 reaches half a voxel below zero, the last voxel of chunk -1 stops there.
+        let cases = [
+            (-0.2, 0),
+            (-0.3, -1),
+            (7.7, 0),
+            (7.8, 1),
+            (-7.8, -1),
+            (-8.3, -2),
+        ];
+        for (x, chunk) in cases {
+            let point = Vec3::new(x, 0.0, 0.0);
+            assert_eq!(ChunkPos::at(point, voxel_size, SIZE).x, chunk, "{x}");
+            assert_eq!(
+                ChunkPos::at(point, voxel_size, SIZE),
+                WorldVoxelPos::at(point, voxel_size).chunk(SIZE)
+            );
+        }
+    }
+
+    #[test]
+    fn local_padding() {
+        let local = LocalPos::new(0, 7, 15);
+        assert_eq!(local.padded(), [1, 8, 16]);
+        assert_eq!(LocalPos::from_padded(local.padded(), SIZE), Some(local));
+        assert_eq!(LocalPos::from_padded([0, 1, 1], SIZE), None);
+        assert_eq!(LocalPos::from_padded([1, 17, 1], SIZE), None);
+    }
+
+    #[test]
+    fn local_offset_stays_in_the_chunk() {
+        let local = LocalPos::new(0, 7, 15);
+        assert_eq!(
+            local.offset([1, -1, 0], SIZE),
+            Some(LocalPos::new(1, 6, 15))
+        );
+        assert_eq!(local.offset([-1, 0, 0], SIZE), None);
+        assert_eq!(local.offset([0, 0, 1], SIZE), None);
+    }
 
-        // Square the differences, sum them, cast to f64, and take the square root to get the distance
-        ((dx * dx + dy * dy + dz * dz) as f64).sqrt()
+    #[test]
+    fn local_index_is_x_first() {
+        assert_eq!(LocalPos::new(0, 0, 0).index(SIZE), 0);
+        assert_eq!(LocalPos::new(1, 0, 0).index(SIZE), 1);
+        assert_eq!(LocalPos::new(0, 1, 0).index(SIZE), SIZE);
+        assert_eq!(LocalPos::new(0, 0, 1).index(SIZE), SIZE * SIZE);
+        assert_eq!(
+            LocalPos::new(SIZE - 1, SIZE - 1, SIZE - 1).index(SIZE),
+            SIZE * SIZE * SIZE - 1
+        );
     }
 }
@@ -1,15 +1,64 @@
+pub mod brush;
+#[cfg(feature = "bevy")]
+pub mod cave_culling;
+pub mod config;
+#[cfg(feature = "bevy")]
 pub mod despawn;
+pub mod history;
+pub mod raycast;
+#[cfg(feature = "bevy")]
+pub mod remesh;
+#[cfg(feature = "bevy")]
 pub mod render_distance;
+pub mod save;
+#[cfg(feature = "bevy")]
+pub mod side_culling;
+pub mod updates;
+pub mod voxel_world;
 
+#[cfg(feature = "bevy")]
 use bevy::app::{App, Plugin, Update};
-pub use despawn::{despawn_handler, Despawn};
-pub use render_distance::{render_distance_handler, RENDER_DISTANCE};
+pub use brush::{Brush, BrushMode, EditRecord, Shape, VoxelChange};
+pub use config::{WorldConfig, MAX_CHUNK_SIZE};
+pub use history::{EditHistory, HISTORY_LENGTH};
+pub use raycast::RaycastHit;
+pub use save::WORLD_EXTENSION;
+pub use updates::{BlockUpdate, BlockUpdateEvent, BLOCK_TICK, MAX_BLOCK_UPDATES, RANDOM_TICKS};
+pub use voxel_world::VoxelWorld;
+#[cfg(feature = "bevy")]
+pub use {
+    cave_culling::cave_culling_handler,
+    despawn::{despawn_handler, Despawn},
+    remesh::{remesh_handler, REMESH_LIMIT},
+    render_distance::{render_distance_handler, ChunkLoader, RENDER_DISTANCE},
+    side_culling::side_culling_handler,
+    updates::{block_update_handler, BlockTicks},
+};
 
+#[cfg(feature = "bevy")]
+use crate::settings::VoxelSettings;
+
+#[cfg(feature = "bevy")]
 pub struct WorldPlugin;
 
+#[cfg(feature = "bevy")]
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, despawn_handler)
-            .add_systems(Update, render_distance_handler);
+        // The world is created with the chunk size and voxel scale of the settings inserted before this plugin.
+        let settings = app
+            .world
+            .get_resource::<VoxelSettings>()
+            .copied()
+            .unwrap_or_default();
+        app.insert_resource(VoxelWorld::new(settings.config()))
+            .init_resource::<BlockTicks>()
+            .init_resource::<EditHistory>()
+            .add_event::<BlockUpdateEvent>()
+            .add_systems(Update, block_update_handler)
+            .add_systems(Update, despawn_handler)
+            .add_systems(Update, render_distance_handler)
+            .add_systems(Update, remesh_handler)
+            .add_systems(Update, cave_culling_handler)
+            .add_systems(Update, side_culling_handler);
     }
 }
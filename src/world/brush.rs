@@ -0,0 +1,303 @@
+use std::{collections::HashSet, sync::Arc};
+
+use glam::Vec3;
+
+use crate::{
+    mesh::Voxel,
+    physics::Aabb,
+    util::{ChunkPos, WorldVoxelPos},
+};
+
+use super::{VoxelWorld, WorldConfig};
+
+// Public enum `Shape` describing a region of world space by its signed distance function,
+// negative inside the shape and positive outside. Positions and sizes are in world units.
+#[derive(Clone)]
+pub enum Shape {
+    Sphere {
+        center: Vec3,
+        radius: f32,
+    },
+    Cuboid {
+        min: Vec3,
+        max: Vec3,
+    },
+    // An upright cylinder standing on `base`.
+    Cylinder {
+        base: Vec3,
+        radius: f32,
+        height: f32,
+    },
+    // An arbitrary signed distance function, only evaluated inside `bounds`.
+    Sdf {
+        bounds: Aabb,
+        distance: Arc<dyn Fn(Vec3) -> f32 + Send + Sync>,
+    },
+}
+
+impl Shape {
+    // Public method `distance` returns the signed distance from a point to the surface of the shape.
+    pub fn distance(&self, point: Vec3) -> f32 {
+        match self {
+            Self::Sphere { center, radius } => point.distance(*center) - radius,
+            Self::Cuboid { min, max } => {
+                let center = (*min + *max) / 2.0;
+                let half = (*max - *min) / 2.0;
+                let q = (point - center).abs() - half;
+                q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+            }
+            Self::Cylinder {
+                base,
+                radius,
+                height,
+            } => {
+                let offset = point - *base;
+                let radial = Vec3::new(offset.x, 0.0, offset.z).length() - radius;
+                let vertical = (offset.y - height / 2.0).abs() - height / 2.0;
+                let outside = Vec3::new(radial.max(0.0), vertical.max(0.0), 0.0).length();
+                outside + radial.max(vertical).min(0.0)
+            }
+            Self::Sdf { distance, .. } => distance(point),
+        }
+    }
+
+    // Public method `bounds` returns a box containing the whole shape.
+    pub fn bounds(&self) -> Aabb {
+        match self {
+            Self::Sphere { center, radius } => Aabb::new(
+                *center - Vec3::splat(*radius),
+                *center + Vec3::splat(*radius),
+            ),
+            Self::Cuboid { min, max } => Aabb::new(*min, *max),
+            Self::Cylinder {
+                base,
+                radius,
+                height,
+            } => Aabb::new(
+                *base - Vec3::new(*radius, 0.0, *radius),
+                *base + Vec3::new(*radius, *height, *radius),
+            ),
+            Self::Sdf { bounds, .. } => *bounds,
+        }
+    }
+
+    // Public method `voxels` returns the world voxel positions of every voxel whose center lies inside the shape.
+    pub fn voxels<'a>(
+        &'a self,
+        config: &'a WorldConfig,
+    ) -> impl Iterator<Item = WorldVoxelPos> + 'a {
+        self.bounds()
+            .voxels(config)
+            .filter(|position| self.distance(config.voxel_center(*position)) <= 0.0)
+    }
+}
+
+// Public enum `BrushMode` describing what a brush does to the voxels inside its shape.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BrushMode {
+    // Fill the shape with a voxel, replacing anything already there.
+    Add(Voxel),
+    // Empty the shape.
+    Remove,
+    // Recolor the voxels in the shape that aren't empty, leaving empty space alone.
+    Paint(Voxel),
+}
+
+// Public struct `Brush` combining a shape with what to do to the voxels inside it.
+#[derive(Clone)]
+pub struct Brush {
+    pub shape: Shape,
+    pub mode: BrushMode,
+}
+
+impl Brush {
+    pub fn new(shape: Shape, mode: BrushMode) -> Self {
+        Self { shape, mode }
+    }
+}
+
+// Public struct `VoxelChange` recording a single voxel changed by an edit.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct VoxelChange {
+    pub position: WorldVoxelPos,
+    pub before: Voxel,
+    pub after: Voxel,
+}
+
+// Public struct `EditRecord` holding every voxel changed by an edit, in the order they were changed.
+#[derive(Clone, Default, Debug)]
+pub struct EditRecord {
+    pub changes: Vec<VoxelChange>,
+}
+
+impl EditRecord {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // Public method `chunks` returns the positions of the chunks owning the changed voxels.
+    pub fn chunks(&self, config: &WorldConfig) -> HashSet<ChunkPos> {
+        self.changes
+            .iter()
+            .map(|change| change.position.chunk(config.chunk_size))
+            .collect()
+    }
+}
+
+impl VoxelWorld {
+    // Public method `edit` changes a single voxel like `set`, returning a record of the change.
+    pub fn edit(&mut self, position: WorldVoxelPos, voxel: Voxel) -> EditRecord {
+        let mut record = EditRecord::default();
+        match self.set(position, voxel) {
+            Some(before) if before != voxel => record.changes.push(VoxelChange {
+                position,
+                before,
+                after: voxel,
+            }),
+            _ => {}
+        }
+
+        record
+    }
+
+    /// Public method `apply_brush` applies a brush to every voxel inside its shape, across all overlapping chunks,
+    /// and returns a record of the voxels that changed. Voxels of chunks that aren't generated are skipped.
+    /// Every affected chunk is only marked dirty, so it gets remeshed once no matter how many of its voxels changed.
+    /// The changed voxels are relit together, and only those on the boundary of the edit notify their neighbors.
+    pub fn apply_brush(&mut self, brush: &Brush) -> EditRecord {
+        let mut record = EditRecord::default();
+
+        let config = self.config;
+        for position in brush.shape.voxels(&config) {
+            let Some(before) = self.get(position) else {
+                continue;
+            };
+            let after = match brush.mode {
+                BrushMode::Add(voxel) => voxel,
+                BrushMode::Remove => Voxel::Empty,
+                BrushMode::Paint(voxel) if before != Voxel::Empty => voxel,
+                BrushMode::Paint(_) => continue,
+            };
+            if before == after {
+                continue;
+            }
+
+            self.set_quietly(position, after);
+            record.changes.push(VoxelChange {
+                position,
+                before,
+                after,
+            });
+        }
+
+        self.notify(&record);
+        record
+    }
+
+    // Public method `explode` blows a spherical hole of `radius` world units into the terrain around `center`.
+    // Liquids are left in place, so they flow into the hole. Like brushes, only the boundary of the hole is notified.
+    pub fn explode(&mut self, center: Vec3, radius: f32) -> EditRecord {
+        let shape = Shape::Sphere { center, radius };
+
+        let mut record = EditRecord::default();
+        let config = self.config;
+        for position in shape.voxels(&config) {
+            match self.get(position) {
+                None | Some(Voxel::Empty | Voxel::Liquid(..)) => {}
+                Some(before) => {
+                    self.set_quietly(position, Voxel::Empty);
+                    record.changes.push(VoxelChange {
+                        position,
+                        before,
+                        after: Voxel::Empty,
+                    });
+                }
+            }
+        }
+
+        self.notify(&record);
+        record
+    }
+
+    /// Public method `notify` queues the relight and block updates of an edit made with `set_quietly`.
+    /// Every changed voxel is relit, but voxels surrounded by other changed voxels on every side aren't
+    /// notified, their neighbors changed along with them.
+    pub fn notify(&mut self, record: &EditRecord) {
+        let changed: HashSet<WorldVoxelPos> = record
+            .changes
+            .iter()
+            .map(|change| change.position)
+            .collect();
+
+        self.relight
+            .extend(record.changes.iter().map(|change| change.position));
+        self.changed
+            .extend(
+                record
+                    .changes
+                    .iter()
+                    .map(|change| change.position)
+                    .filter(|position| {
+                        position
+                            .neighbors()
+                            .any(|neighbor| !changed.contains(&neighbor))
+                    }),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::STONE, light::Emission, mesh::ChunkMesh, util::Color};
+
+    // Function `world` generates a single empty chunk at the origin.
+    fn world() -> VoxelWorld {
+        let config = WorldConfig::new(8, 1.0);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        world.insert(chunk, &Emission::default());
+        world
+    }
+
+    fn cube() -> Shape {
+        Shape::Cuboid {
+            min: Vec3::splat(0.5),
+            max: Vec3::splat(3.5),
+        }
+    }
+
+    #[test]
+    fn brushes_only_notify_their_boundary() {
+        let mut world = world();
+        let record = world.apply_brush(&Brush::new(cube(), BrushMode::Add(STONE)));
+
+        assert_eq!(record.changes.len(), 27);
+        assert_eq!(world.get(WorldVoxelPos::new(2, 2, 2)), Some(STONE));
+        assert_eq!(world.relight.len(), 27);
+        // The voxel in the middle is surrounded by changed voxels.
+        assert_eq!(world.changed.len(), 26);
+        assert!(!world.changed.contains(&WorldVoxelPos::new(2, 2, 2)));
+    }
+
+    #[test]
+    fn unchanged_voxels_are_skipped() {
+        let mut world = world();
+        world.set(WorldVoxelPos::new(1, 1, 1), STONE);
+        world.relight.clear();
+        world.changed.clear();
+
+        // Only the stone voxel is painted, and it has no changed neighbors.
+        let paint = Voxel::Opaque(Color::new(255, 0, 0));
+        let record = world.apply_brush(&Brush::new(cube(), BrushMode::Paint(paint)));
+        assert_eq!(record.changes.len(), 1);
+        assert_eq!(world.relight, [WorldVoxelPos::new(1, 1, 1)]);
+        assert_eq!(world.changed, [WorldVoxelPos::new(1, 1, 1)]);
+
+        // Removing empties the painted voxel again.
+        let record = world.apply_brush(&Brush::new(cube(), BrushMode::Remove));
+        assert_eq!(record.changes.len(), 1);
+        assert_eq!(world.get(WorldVoxelPos::new(1, 1, 1)), Some(Voxel::Empty));
+    }
+}
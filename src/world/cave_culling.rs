@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    core_pipeline::core_3d::Camera3d,
+    ecs::{
+        query::With,
+        system::{Query, Res},
+    },
+    render::view::Visibility,
+    transform::components::GlobalTransform,
+    utils::HashSet,
+};
+
+use crate::{
+    mesh::{Chunk, Connectivity, LoadedChunks, Side},
+    util::ChunkPos,
+};
+
+use super::VoxelWorld;
+
+// This system hides chunks that can't be seen from the camera's chunk through open space.
+// Uses a breadth-first search over the loaded chunks, only entering a chunk through a face that is
+// connected to the face it leaves through, and never travelling back towards the camera.
+pub fn cave_culling_handler(
+    // Query for the camera's global transform.
+    query: Query<&GlobalTransform, With<Camera3d>>,
+    // Resource containing currently loaded chunks.
+    loaded_chunks: Res<LoadedChunks>,
+    // Query for the chunks and their visibility.
+    mut chunks: Query<(&Chunk, &mut Visibility)>,
+    // The world, for its chunk size.
+    world: Res<VoxelWorld>,
+) {
+    // Get the camera's transform.
+    let transform = query.get_single().expect("There should be a camera");
+
+    // Calculate the chunk coordinates the camera is currently in.
+    let camera_position = world.config.chunk_at(transform.translation());
+
+    // Chunks without a mesh are empty or still loading, so they are treated as fully open.
+    let connectivity = |position: &ChunkPos| {
+        loaded_chunks
+            .0
+            .get(position)
+            .and_then(|entity| chunks.get(*entity).ok())
+            .map(|(chunk, _)| chunk.connectivity)
+            .unwrap_or(Connectivity::ALL)
+    };
+
+    // Each entry holds the chunk, the face it was entered through and the directions travelled so far.
+    let mut queue = VecDeque::new();
+    let mut visible = HashSet::new();
+
+    queue.push_back((camera_position, None, 0u8));
+    visible.insert(camera_position);
+
+    while let Some((position, entered, directions)) = queue.pop_front() {
+        let connectivity = connectivity(&position);
+
+        for side in 0..6 {
+            // The opposite side shares the axis and flips the direction.
+            let opposite = side ^ 1;
+
+            // Never travel back towards the camera.
+            if directions & (1 << opposite) != 0 {
+                continue;
+            }
+
+            // The face we leave through has to be reachable from the face we came in through.
+            if let Some(entered) = entered {
+                if !connectivity.connected(entered, side) {
+                    continue;
+                }
+            }
+
+            let neighbor = position + Side::from(side).offset();
+
+            // Only walk through chunks that are loaded.
+            if !loaded_chunks.0.contains_key(&neighbor) {
+                continue;
+            }
+
+            if visible.insert(neighbor) {
+                queue.push_back((neighbor, Some(opposite), directions | (1 << side)));
+            }
+        }
+    }
+
+    // Hide every chunk that wasn't reached, only touching the visibility when it changes.
+    for (chunk, mut visibility) in chunks.iter_mut() {
+        let target = if visible.contains(&chunk.position) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        if *visibility != target {
+            *visibility = target;
+        }
+    }
+}
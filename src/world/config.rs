@@ -0,0 +1,179 @@
+use glam::Vec3;
+
+use crate::{
+    mesh::{CHUNK_SIZE, MAX_LOD, VOXEL_SIZE},
+    util::{ChunkPos, LocalPos, WorldVoxelPos},
+};
+
+/// The largest chunk size, which keeps a chunk and its padding under 17 million voxels.
+pub const MAX_CHUNK_SIZE: usize = 256;
+
+// Public struct `WorldConfig` holding the chunk size and voxel scale of a world.
+// Every conversion between world space, world voxel positions, chunk positions and local positions
+// goes through it, so the sizes can be changed without touching the code using them.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct WorldConfig {
+    // The number of voxels along each axis of a chunk.
+    pub chunk_size: usize,
+    // The size of a voxel in world units.
+    pub voxel_size: f32,
+}
+
+impl WorldConfig {
+    // Public method `new` creates a configuration, panicking if the sizes aren't valid, see `try_new`.
+    pub fn new(chunk_size: usize, voxel_size: f32) -> Self {
+        Self::try_new(chunk_size, voxel_size).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Public method `try_new` creates a configuration, checking the chunk size can be merged by every level of detail.
+    /// Chunks are downsampled by up to `2^MAX_LOD` voxels along each axis, so their size must be a multiple of it,
+    /// and no larger than `MAX_CHUNK_SIZE`.
+    pub fn try_new(chunk_size: usize, voxel_size: f32) -> Result<Self, String> {
+        let factor = 1 << MAX_LOD;
+        if chunk_size == 0 || !chunk_size.is_multiple_of(factor) || chunk_size > MAX_CHUNK_SIZE {
+            return Err(format!(
+                "chunk size {chunk_size} must be a positive multiple of {factor} up to {MAX_CHUNK_SIZE}"
+            ));
+        }
+        if !(voxel_size.is_finite() && voxel_size > 0.0) {
+            return Err(format!("voxel size {voxel_size} must be positive"));
+        }
+
+        Ok(Self {
+            chunk_size,
+            voxel_size,
+        })
+    }
+
+    // Public method `padded` returns the number of voxels along each axis of a chunk, including its padding.
+    pub fn padded(&self) -> usize {
+        self.chunk_size + 2
+    }
+
+    // Public method `chunk_extent` returns the size of a chunk in world units.
+    pub fn chunk_extent(&self) -> f32 {
+        self.chunk_size as f32 * self.voxel_size
+    }
+
+    // Public method `split` converts a world voxel position into the position of its chunk
+    // and its local position within that chunk, rounding towards negative infinity.
+    pub fn split(&self, position: WorldVoxelPos) -> (ChunkPos, LocalPos) {
+        position.split(self.chunk_size)
+    }
+
+    // Public method `join` converts a chunk position and a local position within it back into a world voxel position.
+    pub fn join(&self, chunk: ChunkPos, local: LocalPos) -> WorldVoxelPos {
+        chunk.join(local, self.chunk_size)
+    }
+
+    // Public method `chunk_origin` returns the world voxel position of the first voxel of a chunk.
+    pub fn chunk_origin(&self, chunk: ChunkPos) -> WorldVoxelPos {
+        chunk.origin(self.chunk_size)
+    }
+
+    // Public method `voxel_bounds` returns the first and last world voxel positions of the box of chunks
+    // between two chunk positions, both included and in any order.
+    pub fn voxel_bounds(&self, a: ChunkPos, b: ChunkPos) -> [WorldVoxelPos; 2] {
+        let last = self.chunk_origin(a.max(b) + [1, 1, 1]) - [1, 1, 1];
+        [self.chunk_origin(a.min(b)), last]
+    }
+
+    // Public method `chunk_translation` returns where the mesh of a chunk is placed in world space.
+    pub fn chunk_translation(&self, chunk: ChunkPos) -> Vec3 {
+        chunk.translation(self.chunk_extent())
+    }
+
+    // Public method `chunk_at` returns the position of the chunk a point in world space lies in.
+    pub fn chunk_at(&self, point: Vec3) -> ChunkPos {
+        ChunkPos::at(point, self.voxel_size, self.chunk_size)
+    }
+
+    // Public method `voxel_center` returns the center of a world voxel position in world space,
+    // voxel `n` being centered on `n * voxel_size`.
+    pub fn voxel_center(&self, position: WorldVoxelPos) -> Vec3 {
+        position.center(self.voxel_size)
+    }
+
+    // Public method `voxel_at` returns the world voxel position of the voxel a point in world space lies in.
+    pub fn voxel_at(&self, point: Vec3) -> WorldVoxelPos {
+        WorldVoxelPos::at(point, self.voxel_size)
+    }
+
+    /// Public method `containing` returns every chunk that holds a world voxel position, either inside or
+    /// in its padding, along with the padded position of the voxel within that chunk.
+    /// The chunk owning the voxel comes first.
+    pub fn containing(
+        &self,
+        position: WorldVoxelPos,
+    ) -> impl Iterator<Item = (ChunkPos, [usize; 3])> {
+        let (chunk, local) = self.split(position);
+        let size = self.chunk_size;
+
+        // The chunk offsets along an axis that hold the voxel, and the padded coordinate within them.
+        let offsets = <[usize; 3]>::from(local).map(|local| {
+            [
+                Some((0, local + 1)),
+                (local == 0).then_some((-1, size + 1)),
+                (local == size - 1).then_some((1, 0)),
+            ]
+        });
+
+        let [xs, ys, zs] = offsets;
+        zs.into_iter().flatten().flat_map(move |(dz, z)| {
+            ys.into_iter().flatten().flat_map(move |(dy, y)| {
+                xs.into_iter()
+                    .flatten()
+                    .map(move |(dx, x)| (chunk.offset([dx, dy, dz]), [x, y, z]))
+            })
+        })
+    }
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self::new(CHUNK_SIZE, VOXEL_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voxel_bounds_cover_whole_chunks() {
+        let config = WorldConfig::new(16, 0.1);
+        assert_eq!(
+            config.voxel_bounds(ChunkPos::new(1, 0, -1), ChunkPos::new(-2, 0, -1)),
+            [
+                WorldVoxelPos::new(-32, 0, -16),
+                WorldVoxelPos::new(31, 15, -1)
+            ]
+        );
+    }
+
+    #[test]
+    fn containing_lists_the_owner_first() {
+        let config = WorldConfig::new(16, 0.1);
+        let containing: Vec<_> = config.containing(WorldVoxelPos::new(-16, 5, 15)).collect();
+        assert_eq!(
+            containing,
+            [
+                (ChunkPos::new(-1, 0, 0), [1, 6, 16]),
+                (ChunkPos::new(-2, 0, 0), [17, 6, 16]),
+                (ChunkPos::new(-1, 0, 1), [1, 6, 0]),
+                (ChunkPos::new(-2, 0, 1), [17, 6, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn sizes_are_validated() {
+        assert!(WorldConfig::try_new(8, 0.5).is_ok());
+        assert!(WorldConfig::try_new(MAX_CHUNK_SIZE, 0.5).is_ok());
+        assert!(WorldConfig::try_new(0, 0.5).is_err());
+        assert!(WorldConfig::try_new(12, 0.5).is_err());
+        assert!(WorldConfig::try_new(MAX_CHUNK_SIZE + 8, 0.5).is_err());
+        assert!(WorldConfig::try_new(8, -1.0).is_err());
+        assert!(WorldConfig::try_new(8, f32::INFINITY).is_err());
+    }
+}
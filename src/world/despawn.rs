@@ -1,8 +1,11 @@
-use bevy::ecs::{
-    component::Component,
-    entity::Entity,
-    query::With,
-    system::{Commands, Query},
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        system::{Commands, Query},
+    },
+    hierarchy::DespawnRecursiveExt,
 };
 
 #[derive(Component)]
@@ -14,7 +17,7 @@ pub fn despawn_handler(
 ) {
     // Iterate over each entity in the query
     for entity in despawns.iter_mut() {
-        // Despawn the entity
-        commands.entity(entity).despawn();
+        // Despawn the entity along with the side meshes of the chunk
+        commands.entity(entity).despawn_recursive();
     }
 }
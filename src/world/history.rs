@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::system::Resource;
+
+use crate::util::ChunkPos;
+
+use super::{EditRecord, VoxelWorld};
+
+/// The number of edits kept by default for undoing.
+pub const HISTORY_LENGTH: usize = 100;
+
+// Public struct `EditHistory` holding the edits that can be undone and the undone edits that can be redone.
+// The voxels of edited chunks are stored by the `VoxelWorld` when unloaded, so edits can be undone after moving away,
+// without keeping whole chunks in memory.
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct EditHistory {
+    undo: VecDeque<EditRecord>,
+    redo: Vec<EditRecord>,
+    // The most edits kept for undoing, the oldest ones are forgotten first.
+    pub length: usize,
+}
+
+// Functions `thaw` and `freeze` put the stored voxels of the unloaded chunks an edit touched back into the world
+// while it is undone or redone, and store them again afterwards.
+fn thaw(world: &mut VoxelWorld, record: &EditRecord) -> Vec<ChunkPos> {
+    let config = world.config;
+    record
+        .chunks(&config)
+        .into_iter()
+        .filter(|chunk| world.thaw(*chunk))
+        .collect()
+}
+
+fn freeze(world: &mut VoxelWorld, thawed: Vec<ChunkPos>) {
+    for chunk in thawed {
+        world.unload(chunk);
+    }
+}
+
+impl EditHistory {
+    pub fn new(length: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            length,
+        }
+    }
+
+    // Public method `push` records a new edit, which makes the undone edits impossible to redo.
+    pub fn push(&mut self, record: EditRecord) {
+        if record.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        self.undo.push_back(record);
+        while self.undo.len() > self.length {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Public method `undo` reverts the last edit, restoring every voxel it changed in reverse order,
+    /// and returns the edit that was undone. The voxels are relit and notified together, like brushes.
+    pub fn undo(&mut self, world: &mut VoxelWorld) -> Option<&EditRecord> {
+        let record = self.undo.pop_back()?;
+        let thawed = thaw(world, &record);
+        for change in record.changes.iter().rev() {
+            world.set_quietly(change.position, change.before);
+        }
+        world.notify(&record);
+        freeze(world, thawed);
+
+        self.redo.push(record);
+        self.redo.last()
+    }
+
+    // Public method `redo` applies the last undone edit again and returns it.
+    pub fn redo(&mut self, world: &mut VoxelWorld) -> Option<&EditRecord> {
+        let record = self.redo.pop()?;
+        let thawed = thaw(world, &record);
+        for change in record.changes.iter() {
+            world.set_quietly(change.position, change.after);
+        }
+        world.notify(&record);
+        freeze(world, thawed);
+
+        self.undo.push_back(record);
+        self.undo.back()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self::new(HISTORY_LENGTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        light::Emission,
+        mesh::{ChunkMesh, Voxel},
+        util::{Color, WorldVoxelPos},
+        world::WorldConfig,
+    };
+
+    const RED: Voxel = Voxel::Opaque(Color::new(255, 0, 0));
+    const BLUE: Voxel = Voxel::Opaque(Color::new(0, 0, 255));
+
+    fn generate(position: ChunkPos) -> ChunkMesh {
+        let mut chunk = ChunkMesh::new(position, WorldConfig::new(8, 0.1));
+        chunk.populate(&Default::default());
+        chunk.compute_light(&Emission::default());
+        chunk
+    }
+
+    // Function `world` generates the two chunks on either side of x = 0.
+    fn world() -> VoxelWorld {
+        let mut world = VoxelWorld::new(WorldConfig::new(8, 0.1));
+        for x in [-1, 0] {
+            world.insert(generate(ChunkPos::new(x, 0, 0)), &Emission::default());
+        }
+        world
+    }
+
+    // The voxels on either side of the chunk boundary.
+    const POSITIONS: [WorldVoxelPos; 2] = [
+        WorldVoxelPos { x: -1, y: 3, z: 3 },
+        WorldVoxelPos { x: 0, y: 3, z: 3 },
+    ];
+
+    // Function `paint` changes both voxels across the boundary in a single edit.
+    fn paint(world: &mut VoxelWorld, voxel: Voxel) -> EditRecord {
+        let mut record = EditRecord::default();
+        for position in POSITIONS {
+            record.changes.extend(world.edit(position, voxel).changes);
+        }
+        record
+    }
+
+    fn voxels(world: &VoxelWorld) -> [Option<Voxel>; 2] {
+        POSITIONS.map(|position| world.get(position))
+    }
+
+    #[test]
+    fn undo_and_redo_across_chunks() {
+        let mut world = world();
+        let before = voxels(&world);
+        let mut history = EditHistory::default();
+        history.push(paint(&mut world, RED));
+        assert_eq!(voxels(&world), [Some(RED); 2]);
+
+        assert_eq!(history.undo(&mut world).unwrap().changes.len(), 2);
+        assert_eq!(voxels(&world), before);
+        assert!(!history.can_undo());
+        assert!(history.undo(&mut world).is_none());
+
+        history.redo(&mut world).unwrap();
+        assert_eq!(voxels(&world), [Some(RED); 2]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn pushing_clears_redo() {
+        let mut world = world();
+        let mut history = EditHistory::default();
+        history.push(paint(&mut world, RED));
+        history.undo(&mut world);
+        assert!(history.can_redo());
+
+        history.push(paint(&mut world, BLUE));
+        assert!(!history.can_redo());
+        assert!(history.redo(&mut world).is_none());
+        assert_eq!(voxels(&world), [Some(BLUE); 2]);
+
+        // Empty edits don't clear anything.
+        history.undo(&mut world);
+        history.push(EditRecord::default());
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn the_oldest_edits_are_dropped() {
+        let mut world = world();
+        let mut history = EditHistory::new(2);
+        for voxel in [RED, BLUE, RED] {
+            history.push(paint(&mut world, voxel));
+        }
+
+        // Only the last two edits can be undone, leaving the first one in place.
+        assert!(history.undo(&mut world).is_some());
+        assert!(history.undo(&mut world).is_some());
+        assert!(history.undo(&mut world).is_none());
+        assert_eq!(voxels(&world), [Some(RED); 2]);
+    }
+
+    #[test]
+    fn undo_after_unloading() {
+        let mut world = world();
+        let before = voxels(&world);
+        let mut history = EditHistory::default();
+        history.push(paint(&mut world, RED));
+
+        let chunks = [ChunkPos::new(-1, 0, 0), ChunkPos::new(0, 0, 0)];
+        for chunk in chunks {
+            world.unload(chunk);
+        }
+        assert!(world.chunks.is_empty());
+
+        // Undoing changes the stored voxels, which come back once the chunks are inserted again.
+        history.undo(&mut world).unwrap();
+        assert!(world.chunks.is_empty());
+        for chunk in chunks {
+            world.insert(generate(chunk), &Emission::default());
+        }
+        assert_eq!(voxels(&world), before);
+
+        history.redo(&mut world).unwrap();
+        assert_eq!(voxels(&world), [Some(RED); 2]);
+    }
+}
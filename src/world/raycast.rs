@@ -0,0 +1,233 @@
+use glam::{IVec3, Vec3};
+
+use crate::{
+    mesh::{Axis, Side, Voxel},
+    util::WorldVoxelPos,
+};
+
+use super::VoxelWorld;
+
+// Public struct `RaycastHit` describing the voxel a ray hit.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RaycastHit {
+    // The world voxel position of the voxel that was hit.
+    pub position: WorldVoxelPos,
+    // The side of the voxel the ray entered through.
+    pub side: Side,
+    // The distance from the origin of the ray to the hit, in world units.
+    pub distance: f32,
+    // The voxel that was hit.
+    pub voxel: Voxel,
+}
+
+impl VoxelWorld {
+    /// Public method `raycast` marches a ray through the voxel grid using the Amanatides–Woo algorithm.
+    /// The origin and direction are in world space, where voxel `n` is centered on `n * voxel_size`.
+    /// Returns the first voxel within `max_distance` for which `filter` returns true,
+    /// skipping over chunks that aren't generated. The ray stops once it leaves the box of generated chunks,
+    /// so it ends even when `max_distance` is infinite.
+    pub fn raycast(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        filter: impl Fn(Voxel) -> bool,
+    ) -> Option<RaycastHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return None;
+        }
+        let [min, max] = self
+            .bounds()?
+            .map(|bound| IVec3::new(bound.x, bound.y, bound.z));
+
+        // Move into grid space where every voxel spans one unit starting at its position.
+        let voxel_size = self.config.voxel_size;
+        let start = origin / voxel_size + Vec3::splat(0.5);
+        let mut voxel = start.floor().as_ivec3();
+        let step = direction.signum().as_ivec3();
+
+        // The distance along the ray to the next voxel boundary on each axis, and between boundaries.
+        let mut next = Vec3::ZERO;
+        let mut delta = Vec3::ZERO;
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                next[axis] = f32::INFINITY;
+                delta[axis] = f32::INFINITY;
+                continue;
+            }
+
+            let boundary = voxel[axis] as f32 + if step[axis] > 0 { 1.0 } else { 0.0 };
+            next[axis] = (boundary - start[axis]) / direction[axis] * voxel_size;
+            delta[axis] = voxel_size / direction[axis].abs();
+        }
+
+        // A ray starting inside a voxel is considered to enter it against its main direction.
+        let main = direction.abs().max_element();
+        let mut axis = (0..3).find(|axis| direction[*axis].abs() == main).unwrap();
+        let mut distance = 0.0;
+
+        loop {
+            let position = WorldVoxelPos::new(voxel.x, voxel.y, voxel.z);
+
+            // Nothing can be hit once the ray is outside the generated chunks and moving away from them.
+            let leaving = (0..3).any(|axis| {
+                (voxel[axis] < min[axis] && step[axis] <= 0)
+                    || (voxel[axis] > max[axis] && step[axis] >= 0)
+            });
+            if leaving {
+                return None;
+            }
+
+            if let Some(hit) = self.get(position).filter(|voxel| filter(*voxel)) {
+                return Some(RaycastHit {
+                    position,
+                    side: Side::new(axis_from_index(axis), step[axis] < 0),
+                    distance,
+                    voxel: hit,
+                });
+            }
+
+            // Step into the neighboring voxel across the closest boundary.
+            axis = if next.x < next.y && next.x < next.z {
+                0
+            } else if next.y < next.z {
+                1
+            } else {
+                2
+            };
+
+            distance = next[axis];
+            if distance > max_distance {
+                return None;
+            }
+
+            voxel += IVec3::AXES[axis] * step[axis];
+            next[axis] += delta[axis];
+        }
+    }
+}
+
+// Function `axis_from_index` converts an index into a vector into its `Axis`.
+fn axis_from_index(index: usize) -> Axis {
+    match index {
+        0 => Axis::X,
+        1 => Axis::Y,
+        _ => Axis::Z,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        light::Emission,
+        mesh::ChunkMesh,
+        util::{ChunkPos, Color},
+        world::WorldConfig,
+    };
+
+    const RED: Voxel = Voxel::Opaque(Color::new(255, 0, 0));
+    const BLUE: Voxel = Voxel::Opaque(Color::new(0, 0, 255));
+
+    // Function `world` generates two empty chunks next to each other along X, with voxels set at the given positions.
+    fn world(voxel_size: f32, voxels: &[([i32; 3], Voxel)]) -> VoxelWorld {
+        let config = WorldConfig::new(8, voxel_size);
+        let mut world = VoxelWorld::new(config);
+        for x in 0..2 {
+            let mut chunk = ChunkMesh::new(ChunkPos::new(x, 0, 0), config);
+            chunk.voxels = vec![Voxel::Empty; chunk.size()];
+            world.insert(chunk, &Emission::default());
+        }
+        for (position, voxel) in voxels {
+            world.set(WorldVoxelPos::from(*position), *voxel);
+        }
+        world
+    }
+
+    fn solid(voxel: Voxel) -> bool {
+        voxel != Voxel::Empty
+    }
+
+    #[test]
+    fn hits_voxels_in_neighboring_chunks() {
+        let world = world(1.0, &[([10, 2, 2], RED)]);
+        let hit = world
+            .raycast(Vec3::new(1.0, 2.0, 2.0), Vec3::X, 20.0, solid)
+            .unwrap();
+        assert_eq!(hit.position, WorldVoxelPos::new(10, 2, 2));
+        assert_eq!(hit.side, Side::new(Axis::X, false));
+        assert_eq!(hit.distance, 8.5);
+        assert_eq!(hit.voxel, RED);
+    }
+
+    #[test]
+    fn reports_the_side_entered_through() {
+        let world = world(1.0, &[([4, 4, 4], RED)]);
+        let target = Vec3::splat(4.0);
+        for (axis, direction) in [(Axis::X, Vec3::X), (Axis::Y, Vec3::Y), (Axis::Z, Vec3::Z)] {
+            for positive in [false, true] {
+                let outside = if positive { direction } else { -direction };
+                let hit = world
+                    .raycast(target + outside * 3.0, -outside, 10.0, solid)
+                    .unwrap();
+                assert_eq!(hit.side, Side::new(axis, positive));
+                assert_eq!(hit.distance, 2.5);
+            }
+        }
+    }
+
+    #[test]
+    fn scales_with_the_voxel_size() {
+        let world = world(0.25, &[([5, 1, 1], RED)]);
+        let hit = world
+            .raycast(Vec3::new(0.0, 0.25, 0.25), Vec3::X, 2.0, solid)
+            .unwrap();
+        assert_eq!(hit.position, WorldVoxelPos::new(5, 1, 1));
+        assert!((hit.distance - 1.125).abs() < 1e-5);
+
+        // The same ray at a unit voxel size passes below the voxel.
+        let world = self::world(1.0, &[([5, 1, 1], RED)]);
+        assert!(world
+            .raycast(Vec3::new(0.0, 0.25, 0.25), Vec3::X, 2.0, solid)
+            .is_none());
+    }
+
+    #[test]
+    fn skips_voxels_the_filter_rejects() {
+        let world = world(1.0, &[([3, 2, 2], RED), ([5, 2, 2], BLUE)]);
+        let origin = Vec3::new(0.0, 2.0, 2.0);
+        let hit = world
+            .raycast(origin, Vec3::X, 10.0, |voxel| voxel == BLUE)
+            .unwrap();
+        assert_eq!(hit.position, WorldVoxelPos::new(5, 2, 2));
+        assert!(world.raycast(origin, Vec3::X, 10.0, |_| false).is_none());
+    }
+
+    #[test]
+    fn misses_past_the_max_distance() {
+        let world = world(1.0, &[([10, 2, 2], RED)]);
+        let origin = Vec3::new(1.0, 2.0, 2.0);
+        assert!(world.raycast(origin, Vec3::X, 8.4, solid).is_none());
+        assert!(world.raycast(origin, Vec3::X, 8.6, solid).is_some());
+        assert!(world.raycast(origin, Vec3::ZERO, 8.6, solid).is_none());
+    }
+
+    #[test]
+    fn infinite_rays_end_outside_the_world() {
+        let world = world(1.0, &[([10, 2, 2], RED)]);
+        let origin = Vec3::new(1.0, 2.0, 2.0);
+        for direction in [Vec3::Y, -Vec3::X, Vec3::new(1.0, 1.0, 0.3)] {
+            assert!(world
+                .raycast(origin, direction, f32::INFINITY, solid)
+                .is_none());
+        }
+
+        // Rays starting outside the world still hit it.
+        let hit = world.raycast(Vec3::new(-50.0, 2.0, 2.0), Vec3::X, f32::INFINITY, solid);
+        assert_eq!(hit.unwrap().position, WorldVoxelPos::new(10, 2, 2));
+        assert!(VoxelWorld::default()
+            .raycast(origin, Vec3::X, f32::INFINITY, solid)
+            .is_none());
+    }
+}
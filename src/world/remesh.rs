@@ -0,0 +1,60 @@
+use bevy::ecs::{
+    event::EventWriter,
+    query::With,
+    system::{Query, Res, ResMut},
+};
+
+use crate::mesh::{generation::ComputeTransform, Chunk, ChunkLoadEvent, LoadedChunks};
+
+use super::VoxelWorld;
+
+/// The most chunks remeshed per frame, the others stay dirty until a later frame.
+pub const REMESH_LIMIT: usize = 16;
+
+// This system remeshes loaded chunks whose voxels changed, keeping their current level of detail.
+// Chunks that are still being meshed wait for their mesh first, so chunks that keep changing,
+// such as those with flowing liquid, aren't remeshed over and over without ever showing a mesh.
+pub fn remesh_handler(
+    // Resource containing the voxels of the generated chunks.
+    mut world: ResMut<VoxelWorld>,
+    // Event writer for chunk load events.
+    mut chunk_load_event: EventWriter<ChunkLoadEvent>,
+    // Resource containing currently loaded chunks.
+    loaded_chunks: Res<LoadedChunks>,
+    // Query for the chunks that were meshed before.
+    chunks: Query<&Chunk>,
+    // Query for the chunks that are being meshed.
+    meshing: Query<(), With<ComputeTransform>>,
+) {
+    if world.dirty.is_empty() {
+        return;
+    }
+
+    let dirty: Vec<_> = world.dirty.iter().copied().collect();
+    let mut remeshed = 0;
+
+    for position in dirty {
+        if remeshed == REMESH_LIMIT {
+            break;
+        }
+
+        // Chunks that aren't loaded pick up the change when they are loaded again.
+        let Some(entity) = loaded_chunks.0.get(&position) else {
+            world.dirty.remove(&position);
+            continue;
+        };
+        if meshing.contains(*entity) {
+            continue;
+        }
+        world.dirty.remove(&position);
+
+        // Chunks without a `Chunk` were empty, so they are meshed at full resolution.
+        let lod = chunks
+            .get(*entity)
+            .map(|chunk| chunk.lod)
+            .unwrap_or_default();
+
+        chunk_load_event.send(ChunkLoadEvent { position, lod });
+        remeshed += 1;
+    }
+}
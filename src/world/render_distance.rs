@@ -2,56 +2,70 @@ use std::collections::VecDeque;
 
 use bevy::{
     ecs::{
+        component::Component,
         event::EventWriter,
-        query::With,
+        query::Without,
         system::{Query, Res},
     },
-    transform::components::Transform,
+    transform::components::GlobalTransform,
     utils::HashSet,
 };
-use bevy_flycam::FlyCam;
 
-use crate::{
-    mesh::{ChunkLoadEvent, ChunkUnloadEvent, LoadedChunks, CHUNK_SIZE, VOXEL_SIZE},
-    util::Position,
+use crate::mesh::{
+    generation::ComputeTransform, Chunk, ChunkLoadEvent, ChunkUnloadEvent, LoadedChunks, Lod,
 };
 
+use super::VoxelWorld;
+
 /// The render distance in chunks.
 pub const RENDER_DISTANCE: i32 = 5;
 
-// This system handles loading and unloading chunks based on the player's position.
+// Public struct `ChunkLoader` marks the entity chunks are loaded around, usually the player.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ChunkLoader {
+    // The distance in chunks around the entity that is loaded.
+    pub distance: i32,
+}
+
+impl Default for ChunkLoader {
+    fn default() -> Self {
+        Self {
+            distance: RENDER_DISTANCE,
+        }
+    }
+}
+
+// This system handles loading and unloading chunks based on the position of the chunk loader.
 // Uses a breadth-first search to find all chunks within the render distance.
+// Chunks further away are meshed at a lower level of detail and remeshed when their level changes.
 pub fn render_distance_handler(
-    // Query for the camera's transform and ensure it has a FlyCam component.
-    query: Query<&Transform, With<FlyCam>>,
+    // Query for the chunk loader and its global transform.
+    query: Query<(&GlobalTransform, &ChunkLoader)>,
     // Event writer for chunk load events.
     mut chunk_load_event: EventWriter<ChunkLoadEvent>,
     // Event writer for chunk unload events.
     mut chunk_unload_event: EventWriter<ChunkUnloadEvent>,
     // Resource containing currently loaded chunks.
     loaded_chunks: Res<LoadedChunks>,
+    // Query for meshed chunks that aren't waiting on a new mesh.
+    chunks: Query<&Chunk, Without<ComputeTransform>>,
+    // The world, for its chunk size.
+    world: Res<VoxelWorld>,
 ) {
-    // Get the camera's transform.
-    let transform = query.get_single().expect("There should be a camera");
+    // Get the chunk loader's transform, nothing is loaded without one.
+    let Ok((transform, loader)) = query.get_single() else {
+        return;
+    };
+    let translation = transform.translation();
 
-    // Calculate the chunk coordinates the camera is currently in.
-    let (chunk_x, chunk_y, chunk_z) = (
-        (transform.translation.x / CHUNK_SIZE / VOXEL_SIZE).floor() as i32,
-        (transform.translation.y / CHUNK_SIZE / VOXEL_SIZE).floor() as i32,
-        (transform.translation.z / CHUNK_SIZE / VOXEL_SIZE).floor() as i32,
-    );
+    // Calculate the chunk coordinates the chunk loader is currently in.
+    let player_position = world.config.chunk_at(translation);
 
     // Initialize a queue, a visited set, and a to_be_loaded set.
     let mut queue = VecDeque::new();
     let mut visited = HashSet::new();
     let mut to_be_loaded = HashSet::new();
 
-    // Create a Position struct for the player's position.
-    let player_position = Position {
-        x: chunk_x,
-        y: chunk_y,
-        z: chunk_z,
-    };
     // Add the player's position to the queue and visited set.
     queue.push_back(player_position);
     visited.insert(player_position);
@@ -59,39 +73,39 @@ pub fn render_distance_handler(
     // While there are positions in the queue...
     while let Some(position) = queue.pop_front() {
         // If the position is within the render distance...
-        if position.distance(&player_position) <= RENDER_DISTANCE.into() {
+        if position.distance(&player_position) <= loader.distance.into() {
             // Add the position to the to_be_loaded set.
             to_be_loaded.insert(position);
-            // If the position is not in the loaded chunks...
-            if !loaded_chunks.0.contains_key(&position) {
-                // Send a chunk load event for the position.
-                chunk_load_event.send(ChunkLoadEvent { position });
-            }
-
-            // For each neighbor of the position...
-            for dx in -1..=1 {
-                for dy in -1..=1 {
-                    for dz in -1..=1 {
-                        let neighbor_position = Position {
-                            x: position.x + dx,
-                            y: position.y + dy,
-                            z: position.z + dz,
-                        };
+            // Select the level of detail based on the distance to the player.
+            let lod = Lod::from_distance(position.distance(&player_position));
 
-                        // If the neighbor has not been visited...
-                        if !visited.contains(&neighbor_position) {
-                            // Add the neighbor to the queue and visited set.
-                            queue.push_back(neighbor_position);
-                            visited.insert(neighbor_position);
+            match loaded_chunks.0.get(&position) {
+                // If the position is not in the loaded chunks, send a chunk load event for the position.
+                None => chunk_load_event.send(ChunkLoadEvent { position, lod }),
+                // If the chunk is meshed at a different level of detail, send a chunk load event to remesh it.
+                Some(entity) => {
+                    if let Ok(chunk) = chunks.get(*entity) {
+                        if chunk.lod != lod {
+                            chunk_load_event.send(ChunkLoadEvent { position, lod });
                         }
                     }
                 }
             }
+
+            // For each neighbor of the position...
+            for neighbor_position in position.surrounding() {
+                // If the neighbor has not been visited...
+                if !visited.contains(&neighbor_position) {
+                    // Add the neighbor to the queue and visited set.
+                    queue.push_back(neighbor_position);
+                    visited.insert(neighbor_position);
+                }
+            }
         }
     }
 
     // For each loaded chunk...
-    for (chunk_position, _) in loaded_chunks.0.iter() {
+    for chunk_position in loaded_chunks.0.keys() {
         // If the chunk is not in the to_be_loaded set...
         if !to_be_loaded.contains(chunk_position) {
             // Send a chunk unload event for the chunk.
@@ -0,0 +1,307 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    light::Emission,
+    mesh::{ChunkMesh, GenerationSettings},
+    schematic::{read_varint, write_varint, Schematic},
+    util::{ChunkPos, LocalPos},
+};
+
+use super::{VoxelWorld, WorldConfig};
+
+/// The file extension of saved worlds.
+pub const WORLD_EXTENSION: &str = "vxw";
+
+// The bytes every world file starts with, followed by the version of the format.
+// Version 1 files don't store the chunk size and voxel scale, they always use the defaults.
+const MAGIC: &[u8; 4] = b"VXWD";
+const VERSION: u8 = 2;
+
+// Function `invalid` creates the error returned for malformed files.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Functions `zigzag` and `unzigzag` map signed integers to unsigned ones, so small negative numbers stay small.
+fn zigzag(value: i32) -> u64 {
+    ((value << 1) ^ (value >> 31)) as u32 as u64
+}
+
+fn unzigzag(value: u64) -> i32 {
+    let value = value as u32;
+    ((value >> 1) as i32) ^ -((value & 1) as i32)
+}
+
+impl ChunkMesh {
+    // Public method `write_voxels` writes the voxels of the chunk without its padding, like a schematic.
+    pub fn write_voxels(&self, writer: &mut impl Write) -> io::Result<()> {
+        let size = self.config.chunk_size;
+        let mut schematic = Schematic::new([size; 3]);
+        for i in 0..schematic.voxels.len() {
+            let [x, y, z] = LocalPos::from(schematic.delinearize(i)).padded();
+            schematic.voxels[i] = self.get(x, y, z);
+        }
+        schematic.write(writer)
+    }
+
+    // Public method `read_voxels` replaces the voxels of the chunk, apart from its padding, with voxels written by `write_voxels`.
+    pub fn read_voxels(&mut self, reader: &mut impl Read) -> io::Result<()> {
+        let schematic = Schematic::read(reader)?;
+        if schematic.size != [self.config.chunk_size; 3] {
+            return Err(invalid("chunk of the wrong size"));
+        }
+
+        for (i, voxel) in schematic.voxels.iter().enumerate() {
+            let [x, y, z] = LocalPos::from(schematic.delinearize(i)).padded();
+            self.set(x, y, z, *voxel);
+        }
+        Ok(())
+    }
+}
+
+impl VoxelWorld {
+    /// Public method `write` writes the chunk size and voxel scale of the world, then the voxels and
+    /// scheduled updates of every generated chunk and edited chunk that was unloaded,
+    /// each stored like a schematic so large uniform areas take a few bytes.
+    /// Light isn't stored, it is computed again when the world is read.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_varint(writer, self.tick)?;
+        write_varint(writer, self.config.chunk_size as u64)?;
+        writer.write_all(&self.config.voxel_size.to_le_bytes())?;
+
+        // Write the chunks in a fixed order, so saving the same world twice gives the same file.
+        let mut positions: Vec<ChunkPos> = self
+            .chunks
+            .keys()
+            .chain(self.stored.keys())
+            .copied()
+            .collect();
+        positions.sort_by_key(|position| (position.x, position.y, position.z));
+        write_varint(writer, positions.len() as u64)?;
+
+        for position in positions {
+            for axis in [position.x, position.y, position.z] {
+                write_varint(writer, zigzag(axis))?;
+            }
+
+            // Stored chunks are already written like a schematic, and never have scheduled updates.
+            let Some(chunk) = self.chunks.get(&position) else {
+                writer.write_all(&self.stored[&position])?;
+                write_varint(writer, 0)?;
+                continue;
+            };
+            chunk.write_voxels(writer)?;
+
+            let mut updates: Vec<_> = chunk.updates.iter().collect();
+            updates.sort();
+            write_varint(writer, updates.len() as u64)?;
+            for (local, due) in updates {
+                for axis in <[usize; 3]>::from(*local) {
+                    write_varint(writer, axis as u64)?;
+                }
+                write_varint(writer, *due)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Public method `read` reads a world written by `write`, keeping the chunk size and voxel scale it was saved with.
+    /// The padding of every chunk is generated with `settings` and then synced with its stored neighbors,
+    /// and the light of every chunk is computed again. All chunks are marked as modified, so their voxels are stored when unloaded.
+    pub fn read(
+        reader: &mut impl Read,
+        settings: &GenerationSettings,
+        emission: &Emission,
+    ) -> io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a world"));
+        }
+        if header[4] == 0 || header[4] > VERSION {
+            return Err(invalid("unsupported world version"));
+        }
+
+        let tick = read_varint(reader)?;
+        let config = if header[4] == 1 {
+            WorldConfig::default()
+        } else {
+            let chunk_size = usize::try_from(read_varint(reader)?).unwrap_or(usize::MAX);
+            let mut voxel_size = [0; 4];
+            reader.read_exact(&mut voxel_size)?;
+            WorldConfig::try_new(chunk_size, f32::from_le_bytes(voxel_size))
+                .map_err(|error| invalid(&error))?
+        };
+        let mut world = VoxelWorld {
+            tick,
+            ..VoxelWorld::new(config)
+        };
+
+        let size = config.chunk_size;
+        for _ in 0..read_varint(reader)? {
+            let position = ChunkPos::new(
+                unzigzag(read_varint(reader)?),
+                unzigzag(read_varint(reader)?),
+                unzigzag(read_varint(reader)?),
+            );
+
+            let mut chunk = ChunkMesh::new(position, config);
+            chunk.populate(settings);
+            chunk.read_voxels(reader)?;
+
+            for _ in 0..read_varint(reader)? {
+                let local = [(); 3].map(|_| read_varint(reader).map(|axis| axis as usize));
+                let [x, y, z] = local;
+                let local = [x?, y?, z?];
+                if local.iter().any(|axis| *axis >= size) {
+                    return Err(invalid("update outside of its chunk"));
+                }
+                chunk
+                    .updates
+                    .insert(LocalPos::from(local), read_varint(reader)?);
+            }
+
+            world.modified.insert(position);
+            world.chunks.insert(position, chunk);
+        }
+
+        // Every chunk is taken out and inserted again, which syncs its padding with the stored neighbors
+        // and merges its light with theirs.
+        let mut positions: Vec<ChunkPos> = world.chunks.keys().copied().collect();
+        positions.sort_by_key(|position| (position.x, position.y, position.z));
+        for position in positions {
+            let mut chunk = world.chunks.remove(&position).unwrap();
+            chunk.compute_light(emission);
+            world.insert(chunk, emission);
+        }
+        world.dirty.clear();
+
+        Ok(world)
+    }
+
+    // Public method `save` writes the world to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    // Public method `load` reads a world from a file.
+    pub fn load(
+        path: impl AsRef<Path>,
+        settings: &GenerationSettings,
+        emission: &Emission,
+    ) -> io::Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?), settings, emission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::STONE, util::WorldVoxelPos};
+
+    // Function `world` generates a small world of a few chunks, with an edit and a scheduled update.
+    fn world() -> VoxelWorld {
+        let settings = GenerationSettings::default();
+        let config = WorldConfig::new(8, 0.25);
+        let mut world = VoxelWorld::new(config);
+        for x in -1..=0 {
+            for y in -1..=0 {
+                let mut chunk = ChunkMesh::new(ChunkPos::new(x, y, 0), config);
+                chunk.populate(&settings);
+                world.insert(chunk, &Emission::default());
+            }
+        }
+        world.set(WorldVoxelPos::new(-1, -1, 3), STONE);
+        world.schedule(WorldVoxelPos::new(2, -5, 1), 4);
+        world.tick = 17;
+        world
+    }
+
+    #[test]
+    fn world_round_trips() {
+        let world = world();
+        let mut bytes = Vec::new();
+        world.write(&mut bytes).unwrap();
+
+        let read = VoxelWorld::read(
+            &mut bytes.as_slice(),
+            &GenerationSettings::default(),
+            &Emission::default(),
+        )
+        .unwrap();
+        assert_eq!(read.config, world.config);
+        assert_eq!(read.tick, 17);
+        assert_eq!(read.get(WorldVoxelPos::new(-1, -1, 3)), Some(STONE));
+
+        assert_eq!(read.chunks.len(), world.chunks.len());
+        for position in world.chunks.keys() {
+            let (chunk, stored) = (&read.chunks[position], &world.chunks[position]);
+            assert_eq!(chunk.voxels, stored.voxels, "{position:?}");
+            assert_eq!(chunk.updates, stored.updates, "{position:?}");
+        }
+
+        // Writing the world read back gives the same file.
+        let mut again = Vec::new();
+        read.write(&mut again).unwrap();
+        assert_eq!(again, bytes);
+    }
+
+    #[test]
+    fn stored_chunks_are_written() {
+        let mut world = world();
+        let mut bytes = Vec::new();
+        world.write(&mut bytes).unwrap();
+
+        // The edited chunk is stored when unloaded, and written like it was still loaded.
+        let edited = ChunkPos::new(-1, -1, 0);
+        world.unload(edited);
+        assert!(world.stored.contains_key(&edited));
+        let mut unloaded = Vec::new();
+        world.write(&mut unloaded).unwrap();
+        assert_eq!(unloaded, bytes);
+    }
+
+    // Function `header` writes the start of a version 2 world file with the given chunk and voxel size.
+    fn header(chunk_size: u64, voxel_size: f32) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        write_varint(&mut bytes, 0).unwrap();
+        write_varint(&mut bytes, chunk_size).unwrap();
+        bytes.extend_from_slice(&voxel_size.to_le_bytes());
+        write_varint(&mut bytes, 0).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn invalid_sizes_are_rejected() {
+        let read = |bytes: Vec<u8>| {
+            VoxelWorld::read(
+                &mut bytes.as_slice(),
+                &GenerationSettings::default(),
+                &Emission::default(),
+            )
+        };
+
+        assert!(read(header(16, 0.5)).is_ok());
+        for (chunk_size, voxel_size) in [
+            (0, 0.5),
+            (20, 0.5),
+            (264, 0.5),
+            (u64::MAX, 0.5),
+            (16, 0.0),
+            (16, f32::NAN),
+        ] {
+            let error = read(header(chunk_size, voxel_size)).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{chunk_size}");
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::{
+    core_pipeline::core_3d::Camera3d,
+    ecs::{
+        query::With,
+        system::{Query, Res},
+    },
+    hierarchy::Parent,
+    render::view::Visibility,
+    transform::components::GlobalTransform,
+};
+
+use crate::mesh::{Chunk, ChunkSide, MeshSettings};
+
+use super::VoxelWorld;
+
+// This system hides the side meshes of chunks whose faces all point away from the camera.
+// Only does anything when chunks are meshed with one mesh per side.
+pub fn side_culling_handler(
+    // Query for the camera's global transform.
+    query: Query<&GlobalTransform, With<Camera3d>>,
+    // Options used when meshing chunks.
+    settings: Res<MeshSettings>,
+    // Query for the chunks the side meshes belong to.
+    chunks: Query<&Chunk>,
+    // Query for the side meshes and their visibility.
+    mut sides: Query<(&ChunkSide, &Parent, &mut Visibility)>,
+    // The world, for its chunk size and voxel scale.
+    world: Res<VoxelWorld>,
+) {
+    if !settings.split_sides {
+        return;
+    }
+
+    // Get the camera's transform.
+    let transform = query.get_single().expect("There should be a camera");
+    let camera = transform.translation().to_array();
+
+    for (side, parent, mut visibility) in sides.iter_mut() {
+        let Ok(chunk) = chunks.get(parent.get()) else {
+            continue;
+        };
+
+        // Calculate the bounding box of the chunk, voxels are centered on their position.
+        let size = world.config.chunk_extent();
+        let min = (world.config.chunk_translation(chunk.position) - world.config.voxel_size / 2.0)
+            .to_array();
+        let max = [min[0] + size, min[1] + size, min[2] + size];
+
+        let target = if side.0.faces_point(camera, min, max) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        // Only touch the visibility when it changes.
+        if *visibility != target {
+            *visibility = target;
+        }
+    }
+}
@@ -0,0 +1,249 @@
+#[cfg(feature = "bevy")]
+use std::time::Duration;
+
+#[cfg(feature = "bevy")]
+use bevy::{
+    ecs::{
+        event::{Event, EventWriter},
+        system::{Res, ResMut, Resource},
+    },
+    time::{Time, Timer, TimerMode},
+};
+
+#[cfg(feature = "bevy")]
+use crate::mesh::{LoadedChunks, Voxel};
+use crate::util::{ChunkPos, LocalPos, WorldVoxelPos};
+
+use super::VoxelWorld;
+
+/// The time between block ticks, in seconds.
+pub const BLOCK_TICK: f32 = 0.05;
+
+/// The number of voxels picked for a random tick in every loaded chunk, per block tick.
+pub const RANDOM_TICKS: u32 = 3;
+
+/// The most scheduled updates sent per block tick, the rest wait for the next tick.
+pub const MAX_BLOCK_UPDATES: usize = 1024;
+
+// Public enum `BlockUpdate` describing why a voxel is being updated.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BlockUpdate {
+    // The voxel itself changed.
+    Changed,
+    // The neighboring voxel at the given world voxel position changed.
+    Neighbor(WorldVoxelPos),
+    // An update scheduled with `VoxelWorld::schedule` is due.
+    Scheduled,
+    // The voxel was picked at random, for slow processes such as growth.
+    Random,
+}
+
+// Public struct `BlockUpdateEvent` sent when a voxel is updated.
+// Systems reacting to it decide what, if anything, the voxel does.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "bevy", derive(Event))]
+pub struct BlockUpdateEvent {
+    pub position: WorldVoxelPos,
+    pub update: BlockUpdate,
+}
+
+// Public struct `BlockTicks` keeping track of time between block ticks and how many random ticks they hand out.
+#[cfg(feature = "bevy")]
+#[derive(Resource)]
+pub struct BlockTicks {
+    pub timer: Timer,
+    // The number of voxels picked for a random tick in every loaded chunk, per block tick.
+    pub random_ticks: u32,
+    // The state of the generator picking voxels for random ticks.
+    seed: u64,
+}
+
+#[cfg(feature = "bevy")]
+impl BlockTicks {
+    // Public method `new` creates block ticks `interval` seconds apart, each handing out `random_ticks` per chunk.
+    pub fn new(interval: f32, random_ticks: u32) -> Self {
+        Self {
+            timer: Timer::new(Duration::from_secs_f32(interval), TimerMode::Repeating),
+            random_ticks,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    // Private method `random` returns the next number of a xorshift generator, good enough to pick voxels.
+    fn random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl Default for BlockTicks {
+    fn default() -> Self {
+        Self::new(BLOCK_TICK, RANDOM_TICKS)
+    }
+}
+
+impl VoxelWorld {
+    /// Public method `schedule` queues an update of the voxel at a world voxel position, `delay` ticks from now.
+    /// Updates are stored with the chunk owning the voxel, which is kept when unloaded until they ran
+    /// so they aren't lost, without marking it as edited.
+    /// A voxel queued twice is updated once at the earliest tick, voxels of chunks that aren't generated are ignored.
+    pub fn schedule(&mut self, position: WorldVoxelPos, delay: u64) {
+        let (chunk_position, local) = self.config.split(position);
+        let tick = self.tick + delay.max(1);
+        let Some(chunk) = self.chunks.get_mut(&chunk_position) else {
+            return;
+        };
+
+        chunk
+            .updates
+            .entry(local)
+            .and_modify(|due| *due = (*due).min(tick))
+            .or_insert(tick);
+    }
+
+    // Public method `due_updates` takes up to `limit` updates of a chunk that are due by the current tick.
+    pub fn due_updates(&mut self, chunk_position: ChunkPos, limit: usize) -> Vec<WorldVoxelPos> {
+        let tick = self.tick;
+        let Some(chunk) = self.chunks.get_mut(&chunk_position) else {
+            return Vec::new();
+        };
+
+        let due: Vec<LocalPos> = chunk
+            .updates
+            .iter()
+            .filter(|(_, due)| **due <= tick)
+            .map(|(local, _)| *local)
+            .take(limit)
+            .collect();
+        for local in due.iter() {
+            chunk.updates.remove(local);
+        }
+
+        due.into_iter()
+            .map(|local| self.config.join(chunk_position, local))
+            .collect()
+    }
+}
+
+// This system notifies changed voxels and their neighbors right away, then on every block tick
+// sends the scheduled updates that are due and picks random voxels of every loaded chunk.
+// Scheduled updates of chunks that aren't loaded stay queued until the chunk is loaded again.
+#[cfg(feature = "bevy")]
+pub fn block_update_handler(
+    time: Res<Time>,
+    mut ticks: ResMut<BlockTicks>,
+    mut world: ResMut<VoxelWorld>,
+    loaded_chunks: Res<LoadedChunks>,
+    mut events: EventWriter<BlockUpdateEvent>,
+) {
+    let changed = std::mem::take(&mut world.changed);
+    for position in changed {
+        events.send(BlockUpdateEvent {
+            position,
+            update: BlockUpdate::Changed,
+        });
+        events.send_batch(position.neighbors().map(|neighbor| BlockUpdateEvent {
+            position: neighbor,
+            update: BlockUpdate::Neighbor(position),
+        }));
+    }
+
+    ticks.timer.tick(time.delta());
+    if !ticks.timer.just_finished() {
+        return;
+    }
+    world.tick += 1;
+
+    let chunks: Vec<ChunkPos> = loaded_chunks
+        .0
+        .keys()
+        .filter(|chunk| world.chunks.contains_key(*chunk))
+        .copied()
+        .collect();
+
+    let mut remaining = MAX_BLOCK_UPDATES;
+    for chunk in chunks.iter() {
+        if remaining == 0 {
+            break;
+        }
+        let due = world.due_updates(*chunk, remaining);
+        remaining -= due.len();
+        events.send_batch(due.into_iter().map(|position| BlockUpdateEvent {
+            position,
+            update: BlockUpdate::Scheduled,
+        }));
+    }
+
+    let size = world.config.chunk_size as u64;
+    for chunk in chunks {
+        for _ in 0..ticks.random_ticks {
+            let random = ticks.random();
+            let local = LocalPos::new(
+                (random % size) as usize,
+                (random / size % size) as usize,
+                (random / (size * size) % size) as usize,
+            );
+            let position = world.config.join(chunk, local);
+            // Empty voxels have nothing to do on a random tick.
+            if matches!(world.get(position), Some(voxel) if voxel != Voxel::Empty) {
+                events.send(BlockUpdateEvent {
+                    position,
+                    update: BlockUpdate::Random,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{light::Emission, mesh::ChunkMesh, world::WorldConfig};
+
+    // Function `world` generates a single chunk at the origin.
+    fn world() -> VoxelWorld {
+        let config = WorldConfig::new(8, 0.1);
+        let mut world = VoxelWorld::new(config);
+        let mut chunk = ChunkMesh::new(ChunkPos::new(0, 0, 0), config);
+        chunk.populate(&Default::default());
+        world.insert(chunk, &Emission::default());
+        world
+    }
+
+    #[test]
+    fn scheduled_updates_are_due_after_their_delay() {
+        let mut world = world();
+        let position = WorldVoxelPos::new(1, 2, 3);
+        world.schedule(position, 2);
+        world.schedule(position, 5);
+
+        let chunk = ChunkPos::new(0, 0, 0);
+        world.tick += 1;
+        assert!(world.due_updates(chunk, 10).is_empty());
+        world.tick += 1;
+        assert_eq!(world.due_updates(chunk, 10), [position]);
+        world.tick += 10;
+        assert!(world.due_updates(chunk, 10).is_empty());
+    }
+
+    #[test]
+    fn updates_keep_chunks_until_they_ran() {
+        let mut world = world();
+        let chunk = ChunkPos::new(0, 0, 0);
+        world.schedule(WorldVoxelPos::new(1, 2, 3), 1);
+        assert!(!world.modified.contains(&chunk));
+
+        // The pending update keeps the chunk when it is unloaded.
+        world.unload(chunk);
+        assert!(world.chunks.contains_key(&chunk));
+
+        // Once it ran, the chunk isn't kept anymore.
+        world.tick += 1;
+        assert_eq!(world.due_updates(chunk, 10).len(), 1);
+        world.unload(chunk);
+        assert!(!world.chunks.contains_key(&chunk));
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "bevy")]
+use bevy::ecs::system::Resource;
+
+use crate::{
+    light::Emission,
+    mesh::{ChunkMesh, Voxel},
+    schematic::Schematic,
+    util::{ChunkPos, WorldVoxelPos},
+};
+
+use super::WorldConfig;
+
+// Public struct `VoxelWorld` holding the voxels of every generated chunk, so they can be queried by world position.
+#[derive(Default)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct VoxelWorld {
+    pub chunks: HashMap<ChunkPos, ChunkMesh>,
+    // Chunks that were edited, their voxels are stored when unloaded so the edits aren't lost.
+    pub modified: HashSet<ChunkPos>,
+    // The voxels of edited chunks that were unloaded, written like a schematic so they take little memory.
+    pub stored: HashMap<ChunkPos, Vec<u8>>,
+    // Chunks whose voxels changed since they were last meshed.
+    pub dirty: HashSet<ChunkPos>,
+    // Voxels that changed since the light was last updated.
+    pub relight: Vec<WorldVoxelPos>,
+    // Voxels that changed since their neighbors were last notified.
+    pub changed: Vec<WorldVoxelPos>,
+    // The number of block ticks that have passed.
+    pub tick: u64,
+    // The chunk size and voxel scale of the world.
+    pub config: WorldConfig,
+}
+
+impl VoxelWorld {
+    // Public method `new` creates an empty world with the given chunk size and voxel scale.
+    pub fn new(config: WorldConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    // Public method `get` returns the voxel at a world voxel position, or `None` if its chunk isn't generated.
+    pub fn get(&self, position: WorldVoxelPos) -> Option<Voxel> {
+        let (chunk, local) = self.config.split(position);
+        // Chunk meshes are padded by one voxel on every side.
+        let [x, y, z] = local.padded();
+        self.chunks.get(&chunk).map(|chunk| chunk.get(x, y, z))
+    }
+
+    // Public method `bounds` returns the first and last world voxel positions of the box of chunks holding
+    // every generated chunk, or `None` if there are none.
+    pub fn bounds(&self) -> Option<[WorldVoxelPos; 2]> {
+        let positions = self.chunks.keys().copied();
+        let first = positions.clone().reduce(ChunkPos::min)?;
+        let last = positions.reduce(ChunkPos::max)?;
+        Some(self.config.voxel_bounds(first, last))
+    }
+
+    /// Public method `set` changes the voxel at a world voxel position and returns the previous voxel,
+    /// or `None` if its chunk isn't generated.
+    /// The padding of neighboring chunks is kept in sync, and every chunk that changed is marked dirty
+    /// so it gets remeshed, including neighbors when a voxel on the edge of a chunk changes.
+    /// The voxel and its neighbors are notified of the change through a `BlockUpdateEvent`.
+    pub fn set(&mut self, position: WorldVoxelPos, voxel: Voxel) -> Option<Voxel> {
+        let previous = self.set_quietly(position, voxel)?;
+        if previous != voxel {
+            self.relight.push(position);
+            self.changed.push(position);
+        }
+
+        Some(previous)
+    }
+
+    /// Public method `set_quietly` changes a voxel like `set`, but neither relights it nor notifies it and its neighbors.
+    /// Edits changing many voxels at once use it and queue the relight and block updates for all of them together.
+    pub fn set_quietly(&mut self, position: WorldVoxelPos, voxel: Voxel) -> Option<Voxel> {
+        let previous = self.get(position)?;
+        if previous == voxel {
+            return Some(previous);
+        }
+
+        let (chunk_position, _) = self.config.split(position);
+        self.modified.insert(chunk_position);
+
+        // Write the voxel into every chunk that contains it, either inside or in its padding.
+        for (neighbor, [x, y, z]) in self.config.containing(position) {
+            if let Some(chunk) = self.chunks.get_mut(&neighbor) {
+                chunk.set(x, y, z, voxel);
+                self.dirty.insert(neighbor);
+            }
+        }
+
+        Some(previous)
+    }
+
+    /// Public method `insert` stores the voxels of a newly generated chunk.
+    /// An edited chunk that was unloaded gets its stored voxels back, is lit again with them,
+    /// and its edits are copied into the padding of its generated neighbors.
+    /// Its padding is generated from noise, so any edits made to neighboring chunks are copied into it.
+    /// Its light is then merged with the light of the neighboring chunks.
+    pub fn insert(&mut self, chunk: ChunkMesh, emission: &Emission) {
+        let position = chunk.position;
+        let mut chunk = chunk;
+
+        let restored = match self.stored.remove(&position) {
+            Some(bytes) => chunk.read_voxels(&mut bytes.as_slice()).is_ok(),
+            None => false,
+        };
+        if restored {
+            chunk.compute_light(emission);
+        }
+
+        let edited_neighbor = self.modified.contains(&position)
+            || position
+                .surrounding()
+                .any(|neighbor| self.modified.contains(&neighbor));
+        if edited_neighbor && self.sync_padding(&mut chunk) {
+            self.dirty.insert(position);
+        }
+        self.chunks.insert(position, chunk);
+
+        if restored {
+            for neighbor in position.surrounding() {
+                let Some(mut chunk) = self.chunks.remove(&neighbor) else {
+                    continue;
+                };
+                if self.sync_padding(&mut chunk) {
+                    self.dirty.insert(neighbor);
+                }
+                self.chunks.insert(neighbor, chunk);
+            }
+        }
+
+        self.merge_light(position, emission);
+    }
+
+    /// Private method `sync_padding` copies the voxels of the neighbors of a chunk into its padding,
+    /// reading them from the stored voxels of neighbors that were unloaded. Returns whether any voxel changed.
+    fn sync_padding(&self, chunk: &mut ChunkMesh) -> bool {
+        let origin = self.config.chunk_origin(chunk.position);
+        let mut stored: HashMap<ChunkPos, Option<Schematic>> = HashMap::new();
+        let mut changed = false;
+
+        for i in 0..chunk.size() {
+            let (x, y, z) = chunk.delinearize(i);
+            let padding = [x, y, z]
+                .iter()
+                .any(|p| *p == 0 || *p == chunk.padded() - 1);
+            if !padding {
+                continue;
+            }
+
+            let world = origin + [x as i32 - 1, y as i32 - 1, z as i32 - 1];
+            let voxel = self.get(world).or_else(|| {
+                let (owner, local) = self.config.split(world);
+                stored
+                    .entry(owner)
+                    .or_insert_with(|| {
+                        let bytes = self.stored.get(&owner)?;
+                        Schematic::read(&mut bytes.as_slice()).ok()
+                    })
+                    .as_ref()
+                    .map(|schematic| schematic.get(local.into()))
+            });
+            if let Some(voxel) = voxel {
+                if chunk.get(x, y, z) != voxel {
+                    chunk.set(x, y, z, voxel);
+                    changed = true;
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Public method `unload` forgets the voxels of a chunk, unless it still has scheduled updates.
+    /// The voxels of edited chunks are stored, so they come back when the chunk is inserted again.
+    /// A chunk kept only for its updates is unloaded when unloaded again after they ran.
+    pub fn unload(&mut self, position: ChunkPos) {
+        let pending = self
+            .chunks
+            .get(&position)
+            .is_some_and(|chunk| !chunk.updates.is_empty());
+        if pending {
+            return;
+        }
+
+        let Some(chunk) = self.chunks.remove(&position) else {
+            return;
+        };
+        if self.modified.contains(&position) {
+            let mut bytes = Vec::new();
+            if chunk.write_voxels(&mut bytes).is_ok() {
+                self.stored.insert(position, bytes);
+            }
+        }
+    }
+
+    /// Public method `thaw` puts the stored voxels of an edited chunk that was unloaded back into the world,
+    /// without padding or light, so they can be changed. Unloading the chunk again stores them back.
+    /// Returns whether the chunk was stored.
+    pub fn thaw(&mut self, position: ChunkPos) -> bool {
+        let Some(bytes) = self.stored.remove(&position) else {
+            return false;
+        };
+
+        let mut chunk = ChunkMesh::new(position, self.config);
+        chunk.voxels = vec![Voxel::Empty; chunk.size()];
+        if chunk.read_voxels(&mut bytes.as_slice()).is_err() {
+            self.stored.insert(position, bytes);
+            return false;
+        }
+
+        self.chunks.insert(position, chunk);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{util::Color, world::WorldConfig};
+
+    // A voxel the terrain never generates.
+    const MARKER: Voxel = Voxel::Opaque(Color::new(1, 2, 3));
+
+    fn generate(position: ChunkPos) -> ChunkMesh {
+        let mut chunk = ChunkMesh::new(position, WorldConfig::new(8, 0.1));
+        chunk.populate(&Default::default());
+        chunk.compute_light(&Emission::default());
+        chunk
+    }
+
+    fn world(positions: &[ChunkPos]) -> VoxelWorld {
+        let mut world = VoxelWorld::new(WorldConfig::new(8, 0.1));
+        for position in positions {
+            world.insert(generate(*position), &Emission::default());
+        }
+        world
+    }
+
+    #[test]
+    fn unedited_chunks_are_forgotten() {
+        let chunk = ChunkPos::new(0, 0, 0);
+        let mut world = world(&[chunk]);
+        world.unload(chunk);
+        assert!(world.chunks.is_empty());
+        assert!(world.stored.is_empty());
+    }
+
+    #[test]
+    fn edited_chunks_are_stored_when_unloaded() {
+        let chunk = ChunkPos::new(0, 0, 0);
+        let mut world = world(&[chunk]);
+        let position = WorldVoxelPos::new(3, 4, 5);
+        world.set(position, MARKER);
+
+        world.unload(chunk);
+        assert!(!world.chunks.contains_key(&chunk));
+        // The stored voxels take a fraction of the memory of the chunk.
+        let padded = world.config.padded();
+        assert!(world.stored[&chunk].len() < padded * padded * padded);
+
+        world.insert(generate(chunk), &Emission::default());
+        assert!(world.stored.is_empty());
+        assert_eq!(world.get(position), Some(MARKER));
+    }
+
+    #[test]
+    fn stored_edits_reach_the_padding_of_neighbors() {
+        let [chunk, east] = [ChunkPos::new(0, 0, 0), ChunkPos::new(1, 0, 0)];
+        let mut world = world(&[chunk]);
+        // A voxel on the eastern edge of the chunk lies in the padding of its eastern neighbor.
+        world.set(WorldVoxelPos::new(7, 4, 5), MARKER);
+        world.unload(chunk);
+
+        // Neighbors generated while the chunk is unloaded read the stored voxels.
+        world.insert(generate(east), &Emission::default());
+        assert_eq!(world.chunks[&east].get(0, 5, 6), MARKER);
+
+        // Neighbors with outdated padding are fixed and remeshed when the chunk comes back.
+        let noise = generate(east).get(0, 5, 6);
+        world.chunks.get_mut(&east).unwrap().set(0, 5, 6, noise);
+        world.dirty.clear();
+        world.insert(generate(chunk), &Emission::default());
+        assert_eq!(world.chunks[&east].get(0, 5, 6), MARKER);
+        assert!(world.dirty.contains(&east));
+    }
+
+    #[test]
+    fn thawed_chunks_are_stored_again() {
+        let chunk = ChunkPos::new(0, 0, 0);
+        let mut world = world(&[chunk]);
+        let position = WorldVoxelPos::new(3, 4, 5);
+        let generated = world.get(position).unwrap();
+        world.set(position, MARKER);
+        world.unload(chunk);
+
+        assert!(world.thaw(chunk));
+        assert!(!world.thaw(chunk));
+        assert_eq!(world.set_quietly(position, generated), Some(MARKER));
+        world.unload(chunk);
+
+        world.insert(generate(chunk), &Emission::default());
+        assert_eq!(world.get(position), Some(generated));
+        assert!(world.stored.is_empty());
+    }
+}